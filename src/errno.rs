@@ -45,6 +45,15 @@ fn clear() {
     }
 }
 
+/// Sets the platform-specific errno to `value`
+#[cfg(feature = "signal")]
+pub(crate) fn set_errno(value: i32) {
+    // Safe because errno is a thread-local variable
+    unsafe {
+        *errno_location() = value;
+    }
+}
+
 /// Returns the platform-specific value of errno
 pub fn errno() -> i32 {
     unsafe { *errno_location() }
@@ -1,10 +1,24 @@
 use crate::errno::Errno;
+#[cfg(not(target_os = "redox"))]
+#[cfg(feature = "fs")]
+use crate::unistd::Whence;
 use libc::{self, c_char, c_int, c_uint, size_t, ssize_t};
 use std::ffi::OsString;
 #[cfg(not(target_os = "redox"))]
+#[cfg(feature = "fs")]
+use std::mem;
+#[cfg(not(target_os = "redox"))]
 use std::os::raw;
 use std::os::unix::ffi::OsStringExt;
+#[cfg(all(target_os = "linux", feature = "fs", feature = "ioctl"))]
+use std::os::unix::io::BorrowedFd;
 use std::os::unix::io::RawFd;
+#[cfg(not(target_os = "redox"))]
+#[cfg(feature = "fs")]
+use std::os::unix::io::{AsFd, AsRawFd};
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg(feature = "fs")]
+use std::os::unix::io::{FromRawFd, OwnedFd};
 
 #[cfg(feature = "fs")]
 use crate::{sys::stat::Mode, NixPath, Result};
@@ -425,9 +439,24 @@ libc_bitflags!(
         F_SEAL_GROW;
         /// The file contents cannot be modified.
         F_SEAL_WRITE;
+        /// The file contents cannot be modified through new writable shared
+        /// mappings or `write(2)`, but existing writable shared mappings can
+        /// still modify them.
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        F_SEAL_FUTURE_WRITE;
     }
 );
 
+#[cfg(target_os = "linux")]
+#[cfg(feature = "fs")]
+impl SealFlag {
+    /// The execute permission bits of the file cannot be changed.
+    ///
+    /// Supported since Linux 6.3.
+    pub const F_SEAL_EXEC: SealFlag =
+        unsafe { SealFlag::from_bits_unchecked(0x20) };
+}
+
 #[cfg(feature = "fs")]
 libc_bitflags!(
     /// Additional configuration flags for `fcntl`'s `F_SETFD`.
@@ -438,6 +467,120 @@ libc_bitflags!(
     }
 );
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg(feature = "fs")]
+libc_enum! {
+    /// The type of a lease, set with [`F_SETLEASE`] and returned by
+    /// [`F_GETLEASE`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+    #[repr(i32)]
+    pub enum LeaseType {
+        /// A read lease, broken when the file is opened for writing or
+        /// truncated.  The file must be open for reading.
+        F_RDLCK,
+        /// A write lease, broken when the file is opened at all.  The file
+        /// must be open for writing.
+        F_WRLCK,
+        /// No lease.  Setting it removes the lease.
+        F_UNLCK,
+    }
+    impl TryFrom<i32>
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg(feature = "fs")]
+bitflags::bitflags! {
+    /// Events of a directory to be notified about, set with [`F_NOTIFY`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+    pub struct DnotifyFlags: libc::c_uint {
+        /// A file was accessed.
+        const DN_ACCESS = 0x0000_0001;
+        /// A file was modified.
+        const DN_MODIFY = 0x0000_0002;
+        /// A file was created.
+        const DN_CREATE = 0x0000_0004;
+        /// A file was unlinked.
+        const DN_DELETE = 0x0000_0008;
+        /// A file was renamed.
+        const DN_RENAME = 0x0000_0010;
+        /// The attributes of a file changed.
+        const DN_ATTRIB = 0x0000_0020;
+        /// Keep notifying, instead of only about the first event.
+        const DN_MULTISHOT = 0x8000_0000;
+    }
+}
+
+/// The kind of owner set with [`F_SETOWN_EX`].
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg(feature = "fs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(i32)]
+pub enum OwnerType {
+    /// A thread, which receives the signals itself.
+    F_OWNER_TID = 0,
+    /// A process.
+    F_OWNER_PID = 1,
+    /// A process group.
+    F_OWNER_PGRP = 2,
+}
+
+/// The owner of a file descriptor, which receives its I/O and lease-break
+/// signals.  Used with [`F_SETOWN_EX`] and [`F_GETOWN_EX`].
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg(feature = "fs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(C)]
+pub struct FOwnerEx {
+    type_: c_int,
+    pid: libc::pid_t,
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg(feature = "fs")]
+impl FOwnerEx {
+    /// Create an owner of kind `owner_type` with the id `pid`.
+    ///
+    /// A `pid` of `0` means no owner.
+    pub const fn new(owner_type: OwnerType, pid: libc::pid_t) -> Self {
+        FOwnerEx {
+            type_: owner_type as c_int,
+            pid,
+        }
+    }
+
+    /// Returns the kind of the owner, or `EINVAL` if the kernel reported a
+    /// kind unknown to nix.
+    pub fn owner_type(&self) -> Result<OwnerType> {
+        match self.type_ {
+            0 => Ok(OwnerType::F_OWNER_TID),
+            1 => Ok(OwnerType::F_OWNER_PID),
+            2 => Ok(OwnerType::F_OWNER_PGRP),
+            _ => Err(Errno::EINVAL),
+        }
+    }
+
+    /// Returns the id of the owner, or `0` if there is none.
+    pub const fn pid(&self) -> libc::pid_t {
+        self.pid
+    }
+}
+
+// Missing from libc.  The kernel uses the same values on all architectures
+// that Rust supports.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg(feature = "fs")]
+mod ffi {
+    use libc::c_int;
+
+    pub const F_SETSIG: c_int = 10;
+    pub const F_GETSIG: c_int = 11;
+    pub const F_SETOWN_EX: c_int = 15;
+    pub const F_GETOWN_EX: c_int = 16;
+}
+
 feature! {
 #![feature = "fs"]
 
@@ -478,6 +621,44 @@ pub enum FcntlArg<'a> {
     F_GETPIPE_SZ,
     #[cfg(any(target_os = "linux", target_os = "android"))]
     F_SETPIPE_SZ(c_int),
+    /// Take, or with `F_UNLCK` remove, a lease on the file.  The holder is
+    /// sent the signal set with `F_SETSIG`, or `SIGIO`, when another process
+    /// opens or truncates the file in a conflicting way.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    F_SETLEASE(LeaseType),
+    /// Returns the type of the lease on the file, if any.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    F_GETLEASE,
+    /// Be notified about events of the directory with the signal set with
+    /// `F_SETSIG`, or `SIGIO`.  Empty flags remove the notification.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    F_NOTIFY(DnotifyFlags),
+    /// Set the owner receiving the I/O and lease-break signals of the file.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    F_SETOWN_EX(&'a FOwnerEx),
+    /// Get the owner receiving the I/O and lease-break signals of the file.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    F_GETOWN_EX(&'a mut FOwnerEx),
+    /// Set the signal sent for I/O and lease breaks, by number so that
+    /// real-time signals can be used.  `0` restores the default of `SIGIO`.
+    ///
+    /// With a signal other than `SIGIO`, its handler or `signalfd` is told
+    /// which file descriptor the event is about.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    F_SETSIG(c_int),
+    /// Returns the signal set with `F_SETSIG`, or `0` for the default.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    F_GETSIG,
+    /// Set the amount of read-ahead for sequential access in bytes.  `0`
+    /// disables read-ahead and a negative amount restores the default.
+    #[cfg(target_os = "freebsd")]
+    F_READAHEAD(c_int),
+    /// Enable or disable read-ahead for the file.
+    #[cfg(any(target_os = "freebsd", target_os = "ios", target_os = "macos"))]
+    F_RDAHEAD(bool),
+    /// Start reading a range of the file into the buffer cache.
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    F_RDADVISE(&'a libc::radvisory),
     // TODO: Rest of flags
 }
 
@@ -538,6 +719,34 @@ pub fn fcntl(fd: RawFd, arg: FcntlArg) -> Result<c_int> {
             F_GETPIPE_SZ => libc::fcntl(fd, libc::F_GETPIPE_SZ),
             #[cfg(any(target_os = "linux", target_os = "android"))]
             F_SETPIPE_SZ(size) => libc::fcntl(fd, libc::F_SETPIPE_SZ, size),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            F_SETLEASE(lease) => {
+                libc::fcntl(fd, libc::F_SETLEASE, lease as c_int)
+            }
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            F_GETLEASE => libc::fcntl(fd, libc::F_GETLEASE),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            F_NOTIFY(flags) => {
+                libc::fcntl(fd, libc::F_NOTIFY, flags.bits() as libc::c_ulong)
+            }
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            F_SETOWN_EX(owner) => libc::fcntl(fd, ffi::F_SETOWN_EX, owner),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            F_GETOWN_EX(owner) => libc::fcntl(fd, ffi::F_GETOWN_EX, owner),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            F_SETSIG(signal) => libc::fcntl(fd, ffi::F_SETSIG, signal),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            F_GETSIG => libc::fcntl(fd, ffi::F_GETSIG),
+            #[cfg(target_os = "freebsd")]
+            F_READAHEAD(bytes) => libc::fcntl(fd, libc::F_READAHEAD, bytes),
+            #[cfg(any(
+                target_os = "freebsd",
+                target_os = "ios",
+                target_os = "macos"
+            ))]
+            F_RDAHEAD(on) => libc::fcntl(fd, libc::F_RDAHEAD, on as c_int),
+            #[cfg(any(target_os = "ios", target_os = "macos"))]
+            F_RDADVISE(advice) => libc::fcntl(fd, libc::F_RDADVISE, advice),
         }
     };
 
@@ -577,6 +786,546 @@ pub fn flock(fd: RawFd, arg: FlockArg) -> Result<()> {
 
     Errno::result(res).map(drop)
 }
+
+/// The type of a [`FileLock`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LockType {
+    /// A shared lock.  The file must be open for reading.
+    Read,
+    /// An exclusive lock.  The file must be open for writing.
+    Write,
+}
+
+/// A lock on a range of bytes of a file.
+///
+/// Built with [`FileLock::new`] and the setters, it covers the whole file by
+/// default, and is taken with [`lock`](FileLock::lock), which returns a guard
+/// releasing it on drop.
+///
+/// By default it is a POSIX record lock.  Those are owned by the process,
+/// never conflict with other locks of the same process, and are all released
+/// as soon as the process closes *any* file descriptor for the file.  On
+/// Linux, [`ofd`](FileLock::ofd) selects an open file description lock
+/// instead, which is owned by the open file description, like a [`Flock`].
+///
+/// For more information, see [`fcntl(2)`].
+///
+/// [`fcntl(2)`]: https://man7.org/linux/man-pages/man2/fcntl.2.html
+#[cfg(not(target_os = "redox"))]
+#[derive(Clone, Copy, Debug)]
+pub struct FileLock {
+    lock_type: LockType,
+    whence: Whence,
+    start: libc::off_t,
+    len: libc::off_t,
+    blocking: bool,
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    ofd: bool,
+}
+
+#[cfg(not(target_os = "redox"))]
+impl FileLock {
+    /// Create a non-blocking POSIX lock of type `lock_type` on the whole file.
+    pub fn new(lock_type: LockType) -> Self {
+        FileLock {
+            lock_type,
+            whence: Whence::SeekSet,
+            start: 0,
+            len: 0,
+            blocking: false,
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            ofd: false,
+        }
+    }
+
+    /// Set what [`start`](FileLock::start) is relative to.
+    ///
+    /// [`lock`](FileLock::lock) resolves the range against the file offset or
+    /// size at the time it is taken, so the guard releases the same range
+    /// however the file changes afterwards.  Only `SeekSet`, `SeekCur` and
+    /// `SeekEnd` are supported.
+    pub fn whence(mut self, whence: Whence) -> Self {
+        self.whence = whence;
+        self
+    }
+
+    /// Set the offset of the start of the range.
+    pub fn start(mut self, start: libc::off_t) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Set the length of the range.
+    ///
+    /// `0`, the default, extends it to the end of the file, however large
+    /// the file grows.  A negative length covers the bytes before the start.
+    pub fn len(mut self, len: libc::off_t) -> Self {
+        self.len = len;
+        self
+    }
+
+    /// Wait for conflicting locks to be released, instead of failing with
+    /// `EAGAIN` or `EACCES`.
+    pub fn blocking(mut self, blocking: bool) -> Self {
+        self.blocking = blocking;
+        self
+    }
+
+    /// Take an open file description lock, instead of a POSIX one.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(all())))]
+    pub fn ofd(mut self, ofd: bool) -> Self {
+        self.ofd = ofd;
+        self
+    }
+
+    /// Take the lock on `fd`, returning a guard which releases it on drop.
+    ///
+    /// Taking a lock over a range of an already held one of the same owner
+    /// replaces it, so the guards of overlapping locks release each other's
+    /// ranges.
+    pub fn lock<Fd: AsFd>(self, fd: Fd) -> Result<FileLockGuard<Fd>> {
+        let l_type = match self.lock_type {
+            LockType::Read => libc::F_RDLCK,
+            LockType::Write => libc::F_WRLCK,
+        };
+        let lock = self.resolve(&fd)?;
+        lock.fcntl(&fd, lock.set_cmd(), l_type)?;
+        Ok(FileLockGuard { fd, lock })
+    }
+
+    /// Find a lock held by another owner which prevents taking this one on
+    /// `fd`, if any.
+    #[cfg(feature = "process")]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "process"))))]
+    pub fn query<Fd: AsFd>(&self, fd: Fd) -> Result<Option<LockConflict>> {
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        let cmd = if self.ofd {
+            libc::F_OFD_GETLK
+        } else {
+            libc::F_GETLK
+        };
+        #[cfg(not(any(target_os = "android", target_os = "linux")))]
+        let cmd = libc::F_GETLK;
+        let l_type = match self.lock_type {
+            LockType::Read => libc::F_RDLCK,
+            LockType::Write => libc::F_WRLCK,
+        };
+        let flock = self.fcntl(&fd, cmd, l_type)?;
+        let lock_type = match c_int::from(flock.l_type) {
+            libc::F_UNLCK => return Ok(None),
+            libc::F_RDLCK => LockType::Read,
+            _ => LockType::Write,
+        };
+        Ok(Some(LockConflict {
+            lock_type,
+            start: flock.l_start,
+            len: flock.l_len,
+            pid: if flock.l_pid > 0 {
+                Some(crate::unistd::Pid::from_raw(flock.l_pid))
+            } else {
+                None
+            },
+        }))
+    }
+
+    /// Make the range relative to the start of the file.
+    fn resolve<Fd: AsFd>(self, fd: &Fd) -> Result<Self> {
+        let fd = fd.as_fd().as_raw_fd();
+        let base = match self.whence {
+            Whence::SeekSet => 0,
+            Whence::SeekCur => crate::unistd::lseek(fd, 0, Whence::SeekCur)?,
+            Whence::SeekEnd => crate::sys::stat::fstat(fd)?.st_size,
+            #[allow(unreachable_patterns)]
+            _ => return Err(Errno::EINVAL),
+        };
+        let start = base.checked_add(self.start).ok_or(Errno::EOVERFLOW)?;
+        Ok(FileLock {
+            whence: Whence::SeekSet,
+            start,
+            ..self
+        })
+    }
+
+    fn set_cmd(&self) -> c_int {
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        if self.ofd {
+            return if self.blocking {
+                libc::F_OFD_SETLKW
+            } else {
+                libc::F_OFD_SETLK
+            };
+        }
+        if self.blocking {
+            libc::F_SETLKW
+        } else {
+            libc::F_SETLK
+        }
+    }
+
+    fn fcntl<Fd: AsFd>(
+        &self,
+        fd: &Fd,
+        cmd: c_int,
+        l_type: c_int,
+    ) -> Result<libc::flock> {
+        let mut flock: libc::flock = unsafe { mem::zeroed() };
+        flock.l_type = l_type as libc::c_short;
+        flock.l_whence = self.whence as libc::c_short;
+        flock.l_start = self.start;
+        flock.l_len = self.len;
+        let res = unsafe {
+            libc::fcntl(fd.as_fd().as_raw_fd(), cmd, &mut flock)
+        };
+        Errno::result(res).map(|_| flock)
+    }
+}
+
+/// A lock held by another owner, found by [`FileLock::query`].
+#[cfg(all(not(target_os = "redox"), feature = "process"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "process"))))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct LockConflict {
+    /// The type of the lock.
+    pub lock_type: LockType,
+    /// The offset of the start of its range, from the start of the file.
+    pub start: libc::off_t,
+    /// The length of its range, `0` meaning up to the end of the file.
+    pub len: libc::off_t,
+    /// The process holding the lock, if it is a POSIX one.
+    pub pid: Option<crate::unistd::Pid>,
+}
+
+/// A lock taken with [`FileLock::lock`], released when dropped.
+#[cfg(not(target_os = "redox"))]
+#[derive(Debug)]
+pub struct FileLockGuard<Fd: AsFd> {
+    fd: Fd,
+    lock: FileLock,
+}
+
+#[cfg(not(target_os = "redox"))]
+impl<Fd: AsFd> FileLockGuard<Fd> {
+    /// Release the lock, and return the file descriptor it was taken on.
+    ///
+    /// If that fails, the guard is returned along with the error.
+    pub fn unlock(self) -> std::result::Result<Fd, (Self, Errno)> {
+        if let Err(e) = self.release() {
+            return Err((self, e));
+        }
+        let this = mem::ManuallyDrop::new(self);
+        Ok(unsafe { std::ptr::read(&this.fd) })
+    }
+
+    fn release(&self) -> Result<()> {
+        let cmd = FileLock {
+            blocking: false,
+            ..self.lock
+        }
+        .set_cmd();
+        self.lock.fcntl(&self.fd, cmd, libc::F_UNLCK).map(drop)
+    }
+}
+
+#[cfg(not(target_os = "redox"))]
+impl<Fd: AsFd> std::ops::Deref for FileLockGuard<Fd> {
+    type Target = Fd;
+
+    fn deref(&self) -> &Fd {
+        &self.fd
+    }
+}
+
+#[cfg(not(target_os = "redox"))]
+impl<Fd: AsFd> Drop for FileLockGuard<Fd> {
+    fn drop(&mut self) {
+        let res = self.release();
+        debug_assert!(res.is_ok(), "release of a file lock failed");
+    }
+}
+
+/// A file, or anything else owning a file descriptor, with a whole-file
+/// advisory lock taken with [`flock`], released when dropped.
+///
+/// The lock is owned by the open file description, so it is shared with
+/// duplicates of the file descriptor.  `Flock` only dereferences to a shared
+/// reference, so that what it wraps cannot be replaced while locked.
+///
+/// For more information, see [`flock(2)`].
+///
+/// [`flock(2)`]: https://man7.org/linux/man-pages/man2/flock.2.html
+#[cfg(not(target_os = "redox"))]
+#[derive(Debug)]
+pub struct Flock<T: AsFd>(T);
+
+#[cfg(not(target_os = "redox"))]
+impl<T: AsFd> Flock<T> {
+    /// Take a lock on `t`.
+    ///
+    /// Returns `EINVAL` for the unlocking `args`.  On failure, `t` is
+    /// returned along with the error.
+    pub fn lock(t: T, args: FlockArg) -> std::result::Result<Self, (T, Errno)> {
+        let operation = match args {
+            FlockArg::LockShared => libc::LOCK_SH,
+            FlockArg::LockExclusive => libc::LOCK_EX,
+            FlockArg::LockSharedNonblock => libc::LOCK_SH | libc::LOCK_NB,
+            FlockArg::LockExclusiveNonblock => libc::LOCK_EX | libc::LOCK_NB,
+            FlockArg::Unlock | FlockArg::UnlockNonblock => {
+                return Err((t, Errno::EINVAL))
+            }
+        };
+        let res = unsafe { libc::flock(t.as_fd().as_raw_fd(), operation) };
+        match Errno::result(res) {
+            Ok(_) => Ok(Flock(t)),
+            Err(e) => Err((t, e)),
+        }
+    }
+
+    /// Release the lock, and return what it was taken on.
+    ///
+    /// If that fails, the lock is returned along with the error.
+    pub fn unlock(self) -> std::result::Result<T, (Self, Errno)> {
+        let res =
+            unsafe { libc::flock(self.0.as_fd().as_raw_fd(), libc::LOCK_UN) };
+        if let Err(e) = Errno::result(res) {
+            return Err((self, e));
+        }
+        let this = mem::ManuallyDrop::new(self);
+        Ok(unsafe { std::ptr::read(&this.0) })
+    }
+}
+
+#[cfg(not(target_os = "redox"))]
+impl<T: AsFd> std::ops::Deref for Flock<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[cfg(not(target_os = "redox"))]
+impl<T: AsFd> Drop for Flock<T> {
+    fn drop(&mut self) {
+        let res =
+            unsafe { libc::flock(self.0.as_fd().as_raw_fd(), libc::LOCK_UN) };
+        debug_assert!(res == 0, "release of a flock failed");
+    }
+}
+
+/// Route the lease-break and I/O signals of `fd` to a new [`SignalFd`], so
+/// that they can be handled in an event loop.
+///
+/// The calling thread becomes the owner of `fd`, receiving `signal`, which
+/// it blocks.  The `ssi_fd` field of the signals read tells which file they
+/// are about, so the same `signal` can be used for several files, with
+/// [`F_SETOWN_EX`] and [`F_SETSIG`] for all but one of them.
+///
+/// Taking a lease makes the calling process the owner of the file, so call
+/// this after [`F_SETLEASE`].
+///
+/// [`SignalFd`]: crate::sys::signalfd::SignalFd
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg(all(feature = "process", feature = "signal"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(feature = "fs", feature = "process", feature = "signal")))
+)]
+pub fn lease_signalfd<Fd: AsFd>(
+    fd: Fd,
+    signal: crate::sys::signal::Signal,
+) -> Result<crate::sys::signalfd::SignalFd> {
+    use crate::sys::signal::SigSet;
+    use crate::sys::signalfd::{SfdFlags, SignalFd};
+
+    let mut mask = SigSet::empty();
+    mask.add(signal);
+    mask.thread_block()?;
+    let tid = crate::unistd::gettid().as_raw();
+    let owner = FOwnerEx::new(OwnerType::F_OWNER_TID, tid);
+    let fd = fd.as_fd().as_raw_fd();
+    fcntl(fd, F_SETOWN_EX(&owner))?;
+    fcntl(fd, F_SETSIG(signal as c_int))?;
+    SignalFd::with_flags(&mask, SfdFlags::SFD_CLOEXEC | SfdFlags::SFD_NONBLOCK)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl AtFlags {
+    /// For [`name_to_handle_at`], return a handle which identifies the file
+    /// but may not be opened, which works on more file systems.  Requires
+    /// Linux 6.5.
+    ///
+    /// It has the value of `AT_REMOVEDIR`, which `name_to_handle_at` does
+    /// not otherwise use.
+    pub const AT_HANDLE_FID: AtFlags = AtFlags::AT_REMOVEDIR;
+}
+
+/// The largest size of the bytes of a [`FileHandle`].
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const MAX_HANDLE_SZ: usize = 128;
+
+/// A handle identifying a file within its file system, returned by
+/// [`name_to_handle_at`].
+///
+/// It stays valid across renames of the file, and across reboots on file
+/// systems supporting it, and can be persisted with
+/// [`to_bytes`](FileHandle::to_bytes).
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FileHandle {
+    handle_type: c_int,
+    bytes: Vec<u8>,
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl FileHandle {
+    /// Create a handle of the file system specific type `handle_type`, made
+    /// of the opaque `bytes`.
+    ///
+    /// Returns `EINVAL` if there are more than 128 of them.
+    pub fn new(handle_type: c_int, bytes: &[u8]) -> Result<Self> {
+        if bytes.len() > MAX_HANDLE_SZ {
+            return Err(Errno::EINVAL);
+        }
+        Ok(FileHandle {
+            handle_type,
+            bytes: bytes.to_vec(),
+        })
+    }
+
+    /// Returns the file system specific type of the handle.
+    pub fn handle_type(&self) -> c_int {
+        self.handle_type
+    }
+
+    /// Returns the opaque bytes of the handle.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Serialise the handle, as its type in native byte order followed by
+    /// its bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut vec = self.handle_type.to_ne_bytes().to_vec();
+        vec.extend_from_slice(&self.bytes);
+        vec
+    }
+
+    /// Deserialise a handle serialised with
+    /// [`to_bytes`](FileHandle::to_bytes).
+    ///
+    /// Returns `EINVAL` if `bytes` is too short or too long.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        const LEN: usize = mem::size_of::<c_int>();
+        if bytes.len() < LEN {
+            return Err(Errno::EINVAL);
+        }
+        let mut handle_type = [0; LEN];
+        handle_type.copy_from_slice(&bytes[..LEN]);
+        Self::new(c_int::from_ne_bytes(handle_type), &bytes[LEN..])
+    }
+}
+
+/// The id of a mount, as in the first field of `/proc/self/mountinfo`,
+/// returned by [`name_to_handle_at`].
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct MountId(c_int);
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl MountId {
+    /// Returns the raw id of the mount.
+    pub const fn as_raw(self) -> c_int {
+        self.0
+    }
+}
+
+// `struct file_handle`, with room for the largest handle.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[repr(C)]
+struct RawFileHandle {
+    handle_bytes: libc::c_uint,
+    handle_type: c_int,
+    f_handle: [u8; MAX_HANDLE_SZ],
+}
+
+/// Get a handle for the file at `path` relative to `dirfd`, and the id of
+/// the mount it is on.
+///
+/// If `dirfd` is `None`, `path` is relative to the current working
+/// directory.  Pass `Some(fd.as_fd())` to resolve it relative to a directory.
+///
+/// `flags` may contain `AT_EMPTY_PATH`, `AT_SYMLINK_FOLLOW` and
+/// [`AT_HANDLE_FID`](AtFlags::AT_HANDLE_FID).  Returns `EOPNOTSUPP` if the
+/// file system does not support file handles.
+///
+/// For more information, see [`name_to_handle_at(2)`].
+///
+/// [`name_to_handle_at(2)`]: https://man7.org/linux/man-pages/man2/name_to_handle_at.2.html
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn name_to_handle_at<P: ?Sized + NixPath>(
+    dirfd: Option<BorrowedFd<'_>>,
+    path: &P,
+    flags: AtFlags,
+) -> Result<(FileHandle, MountId)> {
+    let mut handle = RawFileHandle {
+        handle_bytes: MAX_HANDLE_SZ as libc::c_uint,
+        handle_type: 0,
+        f_handle: [0; MAX_HANDLE_SZ],
+    };
+    let mut mount_id: c_int = 0;
+    let res = path.with_nix_path(|cstr| unsafe {
+        libc::syscall(
+            libc::SYS_name_to_handle_at,
+            at_rawfd(dirfd.map(|fd| fd.as_raw_fd())),
+            cstr.as_ptr(),
+            &mut handle,
+            &mut mount_id,
+            flags.bits(),
+        )
+    })?;
+    Errno::result(res)?;
+    let handle = FileHandle {
+        handle_type: handle.handle_type,
+        bytes: handle.f_handle[..handle.handle_bytes as usize].to_vec(),
+    };
+    Ok((handle, MountId(mount_id)))
+}
+
+/// Open the file identified by `handle`, on the file system of the mount
+/// `mount_fd` refers to, with the `flags` of [`open`].
+///
+/// The caller needs `CAP_DAC_READ_SEARCH`.  Returns `ESTALE` if the file no
+/// longer exists.
+///
+/// For more information, see [`open_by_handle_at(2)`].
+///
+/// [`open_by_handle_at(2)`]: https://man7.org/linux/man-pages/man2/open_by_handle_at.2.html
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn open_by_handle_at<Fd: AsFd>(
+    mount_fd: Fd,
+    handle: &FileHandle,
+    flags: OFlag,
+) -> Result<OwnedFd> {
+    let mut raw = RawFileHandle {
+        handle_bytes: handle.bytes.len() as libc::c_uint,
+        handle_type: handle.handle_type,
+        f_handle: [0; MAX_HANDLE_SZ],
+    };
+    raw.f_handle[..handle.bytes.len()].copy_from_slice(&handle.bytes);
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_open_by_handle_at,
+            mount_fd.as_fd().as_raw_fd(),
+            &mut raw,
+            flags.bits(),
+        )
+    };
+    Errno::result(res).map(|fd| unsafe { OwnedFd::from_raw_fd(fd as RawFd) })
+}
 }
 
 #[cfg(any(target_os = "android", target_os = "linux"))]
@@ -753,6 +1502,437 @@ pub fn fallocate(
     Errno::result(res).map(drop)
 }
 
+#[cfg(target_os = "linux")]
+libc_bitflags!(
+    /// Flags for [`sync_file_range`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+    pub struct SyncFileRangeFlags: c_uint {
+        /// Wait for write-out of pages in the range that were already
+        /// submitted before starting any new write-out.
+        SYNC_FILE_RANGE_WAIT_BEFORE;
+        /// Start write-out of the dirty pages in the range that are not
+        /// already submitted.
+        SYNC_FILE_RANGE_WRITE;
+        /// Wait for write-out of the pages in the range to complete.
+        SYNC_FILE_RANGE_WAIT_AFTER;
+    }
+);
+
+/// Synchronizes part of a file with the storage device.
+///
+/// Writes back the dirty pages of the `nbytes` bytes of the file starting at
+/// `offset`, or up to the end of the file if `nbytes` is `0`.  Unlike
+/// [`fdatasync`](crate::unistd::fdatasync), neither the file metadata nor
+/// the disk write cache are flushed, so this gives no durability guarantee
+/// on its own; it is meant to spread write-out over time so that a later
+/// `fsync` has little left to do.
+///
+/// `SYNC_FILE_RANGE_WRITE` alone starts write-out without waiting for it.
+///
+/// # See Also
+/// [sync_file_range(2)](https://man7.org/linux/man-pages/man2/sync_file_range.2.html)
+#[cfg(target_os = "linux")]
+pub fn sync_file_range<Fd: AsFd>(
+    fd: Fd,
+    offset: libc::off64_t,
+    nbytes: libc::off64_t,
+    flags: SyncFileRangeFlags,
+) -> Result<()> {
+    let res = unsafe {
+        libc::sync_file_range(
+            fd.as_fd().as_raw_fd(),
+            offset,
+            nbytes,
+            flags.bits(),
+        )
+    };
+    Errno::result(res).map(drop)
+}
+
+/// Reads part of a file into the page cache.
+///
+/// Reads the `count` bytes of the file starting at `offset` so that later
+/// reads of the range are served from memory.  The file must be a regular
+/// file.
+///
+/// # See Also
+/// [readahead(2)](https://man7.org/linux/man-pages/man2/readahead.2.html)
+#[cfg(target_os = "linux")]
+pub fn readahead<Fd: AsFd>(
+    fd: Fd,
+    offset: libc::off64_t,
+    count: usize,
+) -> Result<()> {
+    let res =
+        unsafe { libc::readahead(fd.as_fd().as_raw_fd(), offset, count) };
+    Errno::result(res).map(drop)
+}
+
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+mod fs_ioctl {
+    #![allow(non_camel_case_types)]
+
+    #[repr(C)]
+    pub struct file_clone_range {
+        pub src_fd: i64,
+        pub src_offset: u64,
+        pub src_length: u64,
+        pub dest_offset: u64,
+    }
+
+    #[repr(C)]
+    pub struct file_dedupe_range {
+        pub src_offset: u64,
+        pub src_length: u64,
+        pub dest_count: u16,
+        pub reserved1: u16,
+        pub reserved2: u32,
+    }
+
+    #[repr(C)]
+    pub struct file_dedupe_range_info {
+        pub dest_fd: i64,
+        pub dest_offset: u64,
+        pub bytes_deduped: u64,
+        pub status: i32,
+        pub reserved: u32,
+    }
+
+    #[repr(C)]
+    pub struct fiemap {
+        pub fm_start: u64,
+        pub fm_length: u64,
+        pub fm_flags: u32,
+        pub fm_mapped_extents: u32,
+        pub fm_extent_count: u32,
+        pub fm_reserved: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct fiemap_extent {
+        pub fe_logical: u64,
+        pub fe_physical: u64,
+        pub fe_length: u64,
+        pub fe_reserved64: [u64; 2],
+        pub fe_flags: u32,
+        pub fe_reserved: [u32; 3],
+    }
+
+    // Defined like `ioctl_write_int!` would, which cannot be referred to by
+    // path.
+    pub unsafe fn ficlone(
+        fd: libc::c_int,
+        src_fd: libc::c_int,
+    ) -> crate::Result<libc::c_int> {
+        let request = crate::request_code_write!(
+            0x94,
+            9,
+            std::mem::size_of::<libc::c_int>()
+        );
+        let res = libc::ioctl(
+            fd,
+            request as crate::sys::ioctl::ioctl_num_type,
+            src_fd,
+        );
+        crate::errno::Errno::result(res)
+    }
+
+    crate::ioctl_write_ptr!(ficlonerange, 0x94, 13, file_clone_range);
+    crate::ioctl_readwrite!(fideduperange, 0x94, 54, file_dedupe_range);
+    crate::ioctl_readwrite!(fs_ioc_fiemap, b'f', 11, fiemap);
+}
+
+/// Make `dest` share the contents of `src`, by reflinking their extents,
+/// like `cp --reflink`.
+///
+/// Both files must be on the same file system, which must support it, like
+/// Btrfs or XFS.  `EOPNOTSUPP` is returned otherwise, and `EXDEV` if they
+/// are on different ones.
+///
+/// For more information, see [`ioctl_ficlone(2)`].
+///
+/// [`ioctl_ficlone(2)`]: https://man7.org/linux/man-pages/man2/ioctl_ficlone.2.html
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "ioctl"))))]
+#[doc(alias("FICLONE"))]
+pub fn ficlone<Fd1: AsFd, Fd2: AsFd>(dest: Fd1, src: Fd2) -> Result<()> {
+    let src = src.as_fd().as_raw_fd();
+    unsafe { fs_ioctl::ficlone(dest.as_fd().as_raw_fd(), src) }.map(drop)
+}
+
+/// Make `src_length` bytes of `dest` at `dest_offset` share the contents of
+/// `src` at `src_offset`, like [`ficlone`] does for whole files.
+///
+/// The offsets, and the length unless the range ends at the end of `src`,
+/// must be multiples of the block size of the file system.  A length of `0`
+/// clones up to the end of `src`.
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "ioctl"))))]
+#[doc(alias("FICLONERANGE"))]
+pub fn ficlonerange<Fd1: AsFd, Fd2: AsFd>(
+    dest: Fd1,
+    dest_offset: u64,
+    src: Fd2,
+    src_offset: u64,
+    src_length: u64,
+) -> Result<()> {
+    let range = fs_ioctl::file_clone_range {
+        src_fd: src.as_fd().as_raw_fd().into(),
+        src_offset,
+        src_length,
+        dest_offset,
+    };
+    unsafe { fs_ioctl::ficlonerange(dest.as_fd().as_raw_fd(), &range) }
+        .map(drop)
+}
+
+/// The outcome of [`fideduperange`] for one destination.
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "ioctl"))))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DedupeResult {
+    /// The contents were the same, and this many bytes now share their
+    /// extents with the source.
+    Deduped(u64),
+    /// The contents differ, so nothing was done.
+    Differs,
+    /// Deduplicating failed.
+    Failed(Errno),
+}
+
+/// Make ranges of other files with the same contents as `src_length` bytes of
+/// `src` at `src_offset` share their extents, saving space.
+///
+/// `dests` are the files and offsets of the ranges, and the result of each
+/// of them is returned in the same order.  Unlike [`ficlonerange`], the
+/// contents are compared first, under lock, so that concurrent writes are
+/// never lost.  The file system may deduplicate less than `src_length`
+/// bytes at a time.
+///
+/// For more information, see [`ioctl_fideduperange(2)`].
+///
+/// [`ioctl_fideduperange(2)`]: https://man7.org/linux/man-pages/man2/ioctl_fideduperange.2.html
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "ioctl"))))]
+#[doc(alias("FIDEDUPERANGE"))]
+pub fn fideduperange<Fd: AsFd>(
+    src: Fd,
+    src_offset: u64,
+    src_length: u64,
+    dests: &[(BorrowedFd<'_>, u64)],
+) -> Result<Vec<DedupeResult>> {
+    use fs_ioctl::{file_dedupe_range, file_dedupe_range_info};
+
+    let dest_count = u16::try_from(dests.len()).map_err(|_| Errno::EINVAL)?;
+    // Room for the header and the trailing array, suitably aligned.
+    let size = mem::size_of::<file_dedupe_range>()
+        + dests.len() * mem::size_of::<file_dedupe_range_info>();
+    let mut buf = vec![0u64; size / mem::size_of::<u64>()];
+    let range = buf.as_mut_ptr().cast::<file_dedupe_range>();
+    unsafe {
+        range.write(file_dedupe_range {
+            src_offset,
+            src_length,
+            dest_count,
+            reserved1: 0,
+            reserved2: 0,
+        });
+        let info = range.add(1).cast::<file_dedupe_range_info>();
+        for (i, (fd, offset)) in dests.iter().enumerate() {
+            info.add(i).write(file_dedupe_range_info {
+                dest_fd: fd.as_raw_fd().into(),
+                dest_offset: *offset,
+                bytes_deduped: 0,
+                status: 0,
+                reserved: 0,
+            });
+        }
+        fs_ioctl::fideduperange(src.as_fd().as_raw_fd(), range)?;
+        Ok((0..dests.len())
+            .map(|i| {
+                let info = &*info.add(i);
+                match info.status {
+                    0 => DedupeResult::Deduped(info.bytes_deduped),
+                    1 => DedupeResult::Differs,
+                    errno => DedupeResult::Failed(Errno::from_i32(-errno)),
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+bitflags::bitflags! {
+    /// Flags for [`fiemap`].
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "ioctl"))))]
+    pub struct FiemapFlags: u32 {
+        /// Write back dirty data first, so that it is in extents.
+        const FIEMAP_FLAG_SYNC = 0x0000_0001;
+        /// Map the extended attributes of the file instead of its data.
+        const FIEMAP_FLAG_XATTR = 0x0000_0002;
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+bitflags::bitflags! {
+    /// Properties of an [`Extent`].
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "ioctl"))))]
+    pub struct ExtentFlags: u32 {
+        /// The last extent of the file.
+        const FIEMAP_EXTENT_LAST = 0x0000_0001;
+        /// The location of the data is unknown.
+        const FIEMAP_EXTENT_UNKNOWN = 0x0000_0002;
+        /// The data is not allocated yet, and its location unknown.
+        const FIEMAP_EXTENT_DELALLOC = 0x0000_0004;
+        /// The data is compressed, or otherwise encoded.
+        const FIEMAP_EXTENT_ENCODED = 0x0000_0008;
+        /// The data is encrypted.
+        const FIEMAP_EXTENT_DATA_ENCRYPTED = 0x0000_0080;
+        /// The extent is not aligned to blocks.
+        const FIEMAP_EXTENT_NOT_ALIGNED = 0x0000_0100;
+        /// The data is stored with the metadata.
+        const FIEMAP_EXTENT_DATA_INLINE = 0x0000_0200;
+        /// The data is packed with that of other files.
+        const FIEMAP_EXTENT_DATA_TAIL = 0x0000_0400;
+        /// The extent is allocated, but reads as zeroes.
+        const FIEMAP_EXTENT_UNWRITTEN = 0x0000_0800;
+        /// The extent was merged from blocks, by a file system not using
+        /// extents.
+        const FIEMAP_EXTENT_MERGED = 0x0000_1000;
+        /// The data is shared with other files, like after [`ficlone`].
+        const FIEMAP_EXTENT_SHARED = 0x0000_2000;
+    }
+}
+
+/// A range of a file stored contiguously, returned by [`fiemap`].
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "ioctl"))))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Extent {
+    /// The offset of the range in the file, in bytes.
+    pub logical: u64,
+    /// The offset of the range on the device, in bytes.
+    pub physical: u64,
+    /// The length of the range, in bytes.
+    pub length: u64,
+    /// The properties of the range.
+    pub flags: ExtentFlags,
+}
+
+/// Iterator over the [`Extent`]s of a file, returned by [`fiemap`].
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "ioctl"))))]
+#[derive(Debug)]
+pub struct Fiemap<Fd: AsFd> {
+    fd: Fd,
+    start: u64,
+    end: u64,
+    flags: FiemapFlags,
+    extents: std::vec::IntoIter<Extent>,
+    done: bool,
+}
+
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+impl<Fd: AsFd> Fiemap<Fd> {
+    // How many extents to get with each call.
+    const BATCH: usize = 64;
+
+    fn fill(&mut self) -> Result<()> {
+        use fs_ioctl::{fiemap, fiemap_extent};
+
+        let size = mem::size_of::<fiemap>()
+            + Self::BATCH * mem::size_of::<fiemap_extent>();
+        let mut buf = vec![0u64; size / mem::size_of::<u64>()];
+        let map = buf.as_mut_ptr().cast::<fiemap>();
+        let extents = unsafe {
+            map.write(fiemap {
+                fm_start: self.start,
+                fm_length: self.end - self.start,
+                fm_flags: self.flags.bits(),
+                fm_mapped_extents: 0,
+                fm_extent_count: Self::BATCH as u32,
+                fm_reserved: 0,
+            });
+            fs_ioctl::fs_ioc_fiemap(self.fd.as_fd().as_raw_fd(), map)?;
+            std::slice::from_raw_parts(
+                map.add(1).cast::<fiemap_extent>(),
+                (*map).fm_mapped_extents as usize,
+            )
+        };
+        let extents: Vec<Extent> = extents
+            .iter()
+            .map(|e| Extent {
+                logical: e.fe_logical,
+                physical: e.fe_physical,
+                length: e.fe_length,
+                flags: ExtentFlags::from_bits_truncate(e.fe_flags),
+            })
+            .collect();
+        match extents.last() {
+            Some(last)
+                if !last.flags.contains(ExtentFlags::FIEMAP_EXTENT_LAST) =>
+            {
+                // Stop rather than loop if the extents do not advance.
+                let start = last.logical.saturating_add(last.length);
+                self.done = start <= self.start || start >= self.end;
+                self.start = start;
+            }
+            _ => self.done = true,
+        }
+        self.extents = extents.into_iter();
+        Ok(())
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+impl<Fd: AsFd> Iterator for Fiemap<Fd> {
+    type Item = Result<Extent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(extent) = self.extents.next() {
+                return Some(Ok(extent));
+            }
+            if self.done {
+                return None;
+            }
+            if let Err(e) = self.fill() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+/// Iterate over the extents storing `length` bytes of `fd` at `start`,
+/// querying the file system for more as needed.
+///
+/// Holes are skipped.  Extents overlapping the ends of the range are
+/// returned whole.
+///
+/// For more information, see the [kernel documentation].
+///
+/// [kernel documentation]: https://docs.kernel.org/filesystems/fiemap.html
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "ioctl"))))]
+#[doc(alias("FS_IOC_FIEMAP"))]
+pub fn fiemap<Fd: AsFd>(
+    fd: Fd,
+    start: u64,
+    length: u64,
+    flags: FiemapFlags,
+) -> Fiemap<Fd> {
+    Fiemap {
+        fd,
+        start,
+        end: start.saturating_add(length),
+        flags,
+        extents: Vec::new().into_iter(),
+        done: length == 0,
+    }
+}
+
 /// Argument to [`fspacectl`] describing the range to zero.  The first member is
 /// the file offset, and the second is the length of the region.
 #[cfg(any(target_os = "freebsd"))]
@@ -958,4 +2138,96 @@ pub fn posix_fallocate(
         Ok(errno) => Err(Errno::from_i32(errno)),
     }
 }
+
+/// The primitive used by [`prefetch`] to read a range of a file ahead of use.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "emscripten",
+    target_os = "fuchsia",
+    target_os = "wasi",
+    target_env = "uclibc",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "macos"
+))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Prefetch {
+    /// [`readahead`], which reads the range before returning.
+    #[cfg(target_os = "linux")]
+    Readahead,
+    /// [`posix_fadvise`] with `POSIX_FADV_WILLNEED`, which starts reading
+    /// the range in the background.
+    #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+    Fadvise,
+    /// [`fcntl`] with `F_RDADVISE`, which starts reading the range in the
+    /// background.
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    RdAdvise,
+}
+
+/// Reads the `len` bytes of a file starting at `offset` into the page cache
+/// ahead of use, with the best primitive available on the platform.
+///
+/// On Linux this is [`readahead`], falling back to [`posix_fadvise`] for
+/// files that `readahead` does not support.  On macOS and iOS it is
+/// `F_RDADVISE`, which reads at most `c_int::MAX` bytes.  Elsewhere it is
+/// [`posix_fadvise`].  Returns the primitive that was used.
+///
+/// # Example
+/// ```
+/// # use nix::fcntl::prefetch;
+/// # use std::io::Write;
+/// let mut f = tempfile::tempfile().unwrap();
+/// f.write_all(&[0; 4096]).unwrap();
+/// prefetch(&f, 0, 4096).unwrap();
+/// ```
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "emscripten",
+    target_os = "fuchsia",
+    target_os = "wasi",
+    target_env = "uclibc",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "macos"
+))]
+pub fn prefetch<Fd: AsFd>(
+    fd: Fd,
+    offset: libc::off_t,
+    len: libc::off_t,
+) -> Result<Prefetch> {
+    let fd = fd.as_fd();
+
+    #[cfg(target_os = "linux")]
+    {
+        let count = usize::try_from(len).map_err(|_| Errno::EINVAL)?;
+        match readahead(fd, offset as libc::off64_t, count) {
+            Err(Errno::EINVAL) => (),
+            res => return res.map(|()| Prefetch::Readahead),
+        }
+    }
+
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    {
+        let advice = libc::radvisory {
+            ra_offset: offset,
+            ra_count: len.min(c_int::MAX.into()) as c_int,
+        };
+        fcntl(fd.as_raw_fd(), F_RDADVISE(&advice)).map(|_| Prefetch::RdAdvise)
+    }
+
+    #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+    {
+        posix_fadvise(
+            fd.as_raw_fd(),
+            offset,
+            len,
+            PosixFadviseAdvice::POSIX_FADV_WILLNEED,
+        )
+        .map(|()| Prefetch::Fadvise)
+    }
+}
 }
@@ -24,6 +24,7 @@
 //! * `net` - Networking-related functionality
 //! * `personality` - Set the process execution domain
 //! * `poll` - APIs like `poll` and `select`
+//! * `proc` - Process introspection through the `proc` file system
 //! * `process` - Stuff relating to running processes
 //! * `pthread` - POSIX threads
 //! * `ptrace` - Process tracing and debugging
@@ -124,6 +125,12 @@ feature! {
     #![feature = "poll"]
     pub mod poll;
 }
+#[cfg(any(target_os = "android", target_os = "linux"))]
+feature! {
+    #![feature = "proc"]
+    #[deny(missing_docs)]
+    pub mod proc;
+}
 #[cfg(not(any(target_os = "redox", target_os = "fuchsia")))]
 feature! {
     #![feature = "term"]
@@ -142,14 +149,17 @@ feature! {
 }
 // This can be implemented for other platforms as soon as libc
 // provides bindings for them.
-#[cfg(all(
-    target_os = "linux",
-    any(
-        target_arch = "aarch64",
-        target_arch = "s390x",
-        target_arch = "x86",
-        target_arch = "x86_64"
-    )
+#[cfg(any(
+    all(
+        target_os = "linux",
+        any(
+            target_arch = "aarch64",
+            target_arch = "s390x",
+            target_arch = "x86",
+            target_arch = "x86_64"
+        )
+    ),
+    all(target_os = "freebsd", target_arch = "x86_64")
 ))]
 feature! {
     #![feature = "ucontext"]
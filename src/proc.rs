@@ -0,0 +1,526 @@
+//! Introspection of processes through the `proc` file system.
+//!
+//! The files read here are documented in
+//! [proc(5)](https://man7.org/linux/man-pages/man5/proc.5.html).
+use crate::errno::Errno;
+use crate::sys::mman::ProtFlags;
+use crate::sys::signal::SigSet;
+use crate::unistd::{Gid, Pid, Uid};
+use crate::Result;
+use std::convert::TryFrom;
+use std::ffi::{OsStr, OsString};
+use std::fs::{self, File, ReadDir};
+use std::io::{self, BufRead, BufReader, Lines};
+use std::iter::Peekable;
+use std::ops::Range;
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+fn io_error(e: io::Error) -> Errno {
+    e.raw_os_error().map(Errno::from_i32).unwrap_or(Errno::EIO)
+}
+
+fn parse<T: FromStr>(s: &str) -> Result<T> {
+    s.parse().map_err(|_| Errno::EINVAL)
+}
+
+fn parse_hex<T: TryFrom<u64>>(s: &str) -> Result<T> {
+    let n = u64::from_str_radix(s, 16).map_err(|_| Errno::EINVAL)?;
+    T::try_from(n).map_err(|_| Errno::EINVAL)
+}
+
+/// Memory usage of a [`MemoryMap`], as found in `/proc/<pid>/smaps`.
+///
+/// All sizes are in bytes.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct Smaps {
+    /// The size of the mapping.
+    pub size: u64,
+    /// The amount of the mapping that is resident in memory.
+    pub rss: u64,
+    /// The proportional set size: resident memory, with every page divided
+    /// by the number of processes sharing it.
+    pub pss: u64,
+    /// Resident pages shared with other processes, and unmodified.
+    pub shared_clean: u64,
+    /// Resident pages shared with other processes, and modified.
+    pub shared_dirty: u64,
+    /// Resident pages private to the process, and unmodified.
+    pub private_clean: u64,
+    /// Resident pages private to the process, and modified.
+    pub private_dirty: u64,
+    /// Memory marked as referenced or accessed.
+    pub referenced: u64,
+    /// Memory not belonging to any file.
+    pub anonymous: u64,
+    /// Anonymous memory that has been swapped out.
+    pub swap: u64,
+    /// Memory locked with `mlock` and friends.
+    pub locked: u64,
+}
+
+impl Smaps {
+    /// Fill in the field for a `Key:  value kB` line.  Unknown keys are
+    /// ignored.
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let field = match key {
+            "Size" => &mut self.size,
+            "Rss" => &mut self.rss,
+            "Pss" => &mut self.pss,
+            "Shared_Clean" => &mut self.shared_clean,
+            "Shared_Dirty" => &mut self.shared_dirty,
+            "Private_Clean" => &mut self.private_clean,
+            "Private_Dirty" => &mut self.private_dirty,
+            "Referenced" => &mut self.referenced,
+            "Anonymous" => &mut self.anonymous,
+            "Swap" => &mut self.swap,
+            "Locked" => &mut self.locked,
+            _ => return Ok(()),
+        };
+        let kb = value.trim().trim_end_matches("kB").trim_end();
+        *field = parse::<u64>(kb)? * 1024;
+        Ok(())
+    }
+}
+
+/// A mapping in the address space of a process, as found in
+/// `/proc/<pid>/maps`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct MemoryMap {
+    /// The addresses the mapping spans.
+    pub range: Range<usize>,
+    /// The protection of the mapping.
+    pub prot: ProtFlags,
+    /// Whether the mapping is shared, rather than private and copy-on-write.
+    pub shared: bool,
+    /// The offset of the mapping in the file it maps.
+    pub offset: u64,
+    /// The device of the file the mapping maps.
+    pub dev: libc::dev_t,
+    /// The inode of the file the mapping maps, or 0.
+    pub inode: u64,
+    /// The file the mapping maps.  Some mappings have pseudo-paths instead,
+    /// such as `[heap]`, `[stack]` or `[vdso]`.  Files that have been deleted
+    /// have ` (deleted)` appended.  Anonymous mappings have none.
+    pub path: Option<PathBuf>,
+    /// Memory usage of the mapping, if it was read by [`smaps`].
+    pub smaps: Option<Smaps>,
+}
+
+impl FromStr for MemoryMap {
+    type Err = Errno;
+
+    /// Parse a line of `/proc/<pid>/maps`, or the first line of an entry in
+    /// `/proc/<pid>/smaps`.
+    fn from_str(line: &str) -> Result<Self> {
+        let mut rest = line;
+        let mut field = || {
+            rest = rest.trim_start_matches(' ');
+            let end = rest.find(' ').unwrap_or(rest.len());
+            let (field, tail) = rest.split_at(end);
+            rest = tail;
+            field
+        };
+        let (start, end) = field().split_once('-').ok_or(Errno::EINVAL)?;
+        let perms = field().as_bytes();
+        let offset = field();
+        let (major, minor) = field().split_once(':').ok_or(Errno::EINVAL)?;
+        let inode = field();
+        let path = rest.trim_start_matches(' ');
+        if perms.len() != 4 {
+            return Err(Errno::EINVAL);
+        }
+
+        let mut prot = ProtFlags::PROT_NONE;
+        for (&c, flag) in perms.iter().zip([
+            ProtFlags::PROT_READ,
+            ProtFlags::PROT_WRITE,
+            ProtFlags::PROT_EXEC,
+        ]) {
+            if c != b'-' {
+                prot |= flag;
+            }
+        }
+        Ok(MemoryMap {
+            range: parse_hex(start)?..parse_hex(end)?,
+            prot,
+            shared: perms[3] == b's',
+            offset: parse_hex(offset)?,
+            dev: libc::makedev(parse_hex(major)?, parse_hex(minor)?),
+            inode: parse(inode)?,
+            path: if path.is_empty() {
+                None
+            } else {
+                Some(PathBuf::from(path))
+            },
+            smaps: None,
+        })
+    }
+}
+
+/// Iterator over the [`MemoryMap`]s of a process.
+///
+/// Created by [`maps`] and [`smaps`].
+#[derive(Debug)]
+pub struct MemoryMaps {
+    lines: Peekable<Lines<BufReader<File>>>,
+    smaps: bool,
+}
+
+impl MemoryMaps {
+    fn open(pid: Pid, file: &str, smaps: bool) -> Result<Self> {
+        let file =
+            File::open(format!("/proc/{}/{}", pid, file)).map_err(io_error)?;
+        Ok(MemoryMaps {
+            lines: BufReader::new(file).lines().peekable(),
+            smaps,
+        })
+    }
+}
+
+impl Iterator for MemoryMaps {
+    type Item = Result<MemoryMap>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = match self.lines.next()? {
+            Ok(line) => line,
+            Err(e) => return Some(Err(io_error(e))),
+        };
+        let mut map = match line.parse::<MemoryMap>() {
+            Ok(map) => map,
+            Err(e) => return Some(Err(e)),
+        };
+        if self.smaps {
+            let mut smaps = Smaps::default();
+            // The header of the next entry starts with an address range,
+            // rather than with a key.
+            while let Some(Ok(line)) = self.lines.peek() {
+                let (key, value) = match line.split_once(':') {
+                    Some((key, value)) if !key.contains(' ') => (key, value),
+                    _ => break,
+                };
+                if let Err(e) = smaps.set(key, value) {
+                    return Some(Err(e));
+                }
+                self.lines.next();
+            }
+            map.smaps = Some(smaps);
+        }
+        Some(Ok(map))
+    }
+}
+
+/// Iterate over the memory mappings of the process `pid`, from
+/// `/proc/<pid>/maps`.
+///
+/// Use [`Pid::this`] for the calling process.
+///
+/// # Example
+///
+/// ```
+/// # use nix::proc::maps;
+/// # use nix::unistd::Pid;
+/// let heap = maps(Pid::this())
+///     .unwrap()
+///     .map(|map| map.unwrap())
+///     .find(|map| map.path.as_deref() == Some("[heap]".as_ref()));
+/// ```
+pub fn maps(pid: Pid) -> Result<MemoryMaps> {
+    MemoryMaps::open(pid, "maps", false)
+}
+
+/// Like [`maps`], but reads `/proc/<pid>/smaps` to fill in
+/// [`MemoryMap::smaps`] as well.
+///
+/// This is considerably slower, as the kernel walks the page tables of every
+/// mapping.
+pub fn smaps(pid: Pid) -> Result<MemoryMaps> {
+    MemoryMaps::open(pid, "smaps", true)
+}
+
+/// The scheduling state of a process, as shown by `ps`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ProcessState {
+    /// Running or runnable (`R`).
+    Running,
+    /// Sleeping interruptibly (`S`).
+    Sleeping,
+    /// Sleeping uninterruptibly, usually waiting for I/O (`D`).
+    DiskSleep,
+    /// Stopped by a signal (`T`).
+    Stopped,
+    /// Stopped by a tracer (`t`).
+    TracingStop,
+    /// Exited, but not yet waited for (`Z`).
+    Zombie,
+    /// Exited and waited for (`X`, or `x` on Linux 2.6.33 to 3.13).
+    Dead,
+    /// A parked kernel thread (`P`).
+    Parked,
+    /// An idle kernel thread (`I`).
+    Idle,
+    /// Waking up (`W`, on Linux 2.6.33 to 3.13), or paging before 2.6.
+    Waking,
+    /// Sleeping, but woken up by fatal signals (`K`, on Linux 2.6.33 to
+    /// 3.13).
+    WakeKill,
+    /// A state letter not known to Nix.
+    Other(char),
+}
+
+impl FromStr for ProcessState {
+    type Err = Errno;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s.chars().next() {
+            Some('R') => ProcessState::Running,
+            Some('S') => ProcessState::Sleeping,
+            Some('D') => ProcessState::DiskSleep,
+            Some('T') => ProcessState::Stopped,
+            Some('t') => ProcessState::TracingStop,
+            Some('Z') => ProcessState::Zombie,
+            Some('X') | Some('x') => ProcessState::Dead,
+            Some('P') => ProcessState::Parked,
+            Some('I') => ProcessState::Idle,
+            Some('W') => ProcessState::Waking,
+            Some('K') => ProcessState::WakeKill,
+            Some(c) => ProcessState::Other(c),
+            None => return Err(Errno::EINVAL),
+        })
+    }
+}
+
+/// The real, effective, saved and file system user or group IDs of a
+/// process.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ProcIds<T> {
+    /// The real ID.
+    pub real: T,
+    /// The effective ID.
+    pub effective: T,
+    /// The saved set ID.
+    pub saved: T,
+    /// The file system ID.
+    pub filesystem: T,
+}
+
+impl<T> ProcIds<T> {
+    fn parse(s: &str, f: fn(libc::uid_t) -> T) -> Result<Self> {
+        let mut ids = s.split_ascii_whitespace().map(parse);
+        let mut id =
+            || -> Result<T> { ids.next().ok_or(Errno::EINVAL)?.map(f) };
+        Ok(ProcIds {
+            real: id()?,
+            effective: id()?,
+            saved: id()?,
+            filesystem: id()?,
+        })
+    }
+}
+
+/// The status of a process, as found in `/proc/<pid>/status`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ProcStatus {
+    /// The command name of the process, truncated to 15 bytes.
+    pub name: OsString,
+    /// The scheduling state of the process.
+    pub state: ProcessState,
+    /// The thread group ID, which is the process ID as seen by `getpid`.
+    pub tgid: Pid,
+    /// The thread ID.
+    pub pid: Pid,
+    /// The parent process ID.
+    pub ppid: Pid,
+    /// The process tracing this one, if any.
+    pub tracer_pid: Option<Pid>,
+    /// The user IDs.
+    pub uid: ProcIds<Uid>,
+    /// The group IDs.
+    pub gid: ProcIds<Gid>,
+    /// The number of threads in the thread group.
+    pub threads: usize,
+    /// Signals pending for the thread.
+    pub sig_pending: SigSet,
+    /// Signals pending for the whole thread group.
+    pub shared_pending: SigSet,
+    /// Blocked signals.
+    pub sig_blocked: SigSet,
+    /// Ignored signals.
+    pub sig_ignored: SigSet,
+    /// Signals with a handler installed.
+    pub sig_caught: SigSet,
+    /// The inheritable capability set.
+    pub cap_inheritable: u64,
+    /// The permitted capability set.
+    pub cap_permitted: u64,
+    /// The effective capability set.
+    pub cap_effective: u64,
+    /// The capability bounding set.
+    pub cap_bounding: u64,
+    /// The ambient capability set.  Zero before Linux 4.3.
+    pub cap_ambient: u64,
+}
+
+/// Convert a signal mask of `/proc/<pid>/status` to a [`SigSet`].
+fn sigset(s: &str) -> Result<SigSet> {
+    let mask: u64 = parse_hex(s)?;
+    let mut set = std::mem::MaybeUninit::uninit();
+    unsafe {
+        libc::sigemptyset(set.as_mut_ptr());
+        for signum in 1..=64 {
+            if mask & (1 << (signum - 1)) != 0 {
+                // Fails for signals reserved by the C library.
+                libc::sigaddset(set.as_mut_ptr(), signum);
+            }
+        }
+        Ok(SigSet::from_sigset_t_unchecked(set.assume_init()))
+    }
+}
+
+impl FromStr for ProcStatus {
+    type Err = Errno;
+
+    /// Parse the contents of `/proc/<pid>/status`.
+    fn from_str(s: &str) -> Result<Self> {
+        let mut name = None;
+        let mut state = None;
+        let mut tgid = None;
+        let mut pid = None;
+        let mut ppid = None;
+        let mut tracer_pid = None;
+        let mut uid = None;
+        let mut gid = None;
+        let mut threads = None;
+        let mut sigs = [None; 5];
+        let mut caps = [0; 5];
+        for line in s.lines() {
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key, value.trim()),
+                None => continue,
+            };
+            let pid_of = |v: &str| parse(v).map(Pid::from_raw);
+            match key {
+                "Name" => name = Some(unescape(value)),
+                "State" => state = Some(value.parse()?),
+                "Tgid" => tgid = Some(pid_of(value)?),
+                "Pid" => pid = Some(pid_of(value)?),
+                "PPid" => ppid = Some(pid_of(value)?),
+                "TracerPid" => tracer_pid = Some(pid_of(value)?),
+                "Uid" => uid = Some(ProcIds::parse(value, Uid::from_raw)?),
+                "Gid" => gid = Some(ProcIds::parse(value, Gid::from_raw)?),
+                "Threads" => threads = Some(parse(value)?),
+                "SigPnd" => sigs[0] = Some(sigset(value)?),
+                "ShdPnd" => sigs[1] = Some(sigset(value)?),
+                "SigBlk" => sigs[2] = Some(sigset(value)?),
+                "SigIgn" => sigs[3] = Some(sigset(value)?),
+                "SigCgt" => sigs[4] = Some(sigset(value)?),
+                "CapInh" => caps[0] = parse_hex(value)?,
+                "CapPrm" => caps[1] = parse_hex(value)?,
+                "CapEff" => caps[2] = parse_hex(value)?,
+                "CapBnd" => caps[3] = parse_hex(value)?,
+                "CapAmb" => caps[4] = parse_hex(value)?,
+                _ => (),
+            }
+        }
+        let sig = |i: usize| sigs[i].ok_or(Errno::EINVAL);
+        let tracer_pid = tracer_pid.ok_or(Errno::EINVAL)?;
+        Ok(ProcStatus {
+            name: name.ok_or(Errno::EINVAL)?,
+            state: state.ok_or(Errno::EINVAL)?,
+            tgid: tgid.ok_or(Errno::EINVAL)?,
+            pid: pid.ok_or(Errno::EINVAL)?,
+            ppid: ppid.ok_or(Errno::EINVAL)?,
+            tracer_pid: Some(tracer_pid).filter(|p| p.as_raw() != 0),
+            uid: uid.ok_or(Errno::EINVAL)?,
+            gid: gid.ok_or(Errno::EINVAL)?,
+            threads: threads.ok_or(Errno::EINVAL)?,
+            sig_pending: sig(0)?,
+            shared_pending: sig(1)?,
+            sig_blocked: sig(2)?,
+            sig_ignored: sig(3)?,
+            sig_caught: sig(4)?,
+            cap_inheritable: caps[0],
+            cap_permitted: caps[1],
+            cap_effective: caps[2],
+            cap_bounding: caps[3],
+            cap_ambient: caps[4],
+        })
+    }
+}
+
+/// Undo the escaping of `\n` and `\\` in the `Name` field.
+fn unescape(s: &str) -> OsString {
+    let mut out = Vec::with_capacity(s.len());
+    let mut bytes = s.bytes();
+    while let Some(b) = bytes.next() {
+        match (b, bytes.clone().next()) {
+            (b'\\', Some(b'n')) => {
+                out.push(b'\n');
+                bytes.next();
+            }
+            (b'\\', Some(b'\\')) => {
+                out.push(b'\\');
+                bytes.next();
+            }
+            _ => out.push(b),
+        }
+    }
+    OsStr::from_bytes(&out).to_owned()
+}
+
+/// Read the status of the process `pid` from `/proc/<pid>/status`.
+///
+/// # Example
+///
+/// ```
+/// # use nix::proc::status;
+/// # use nix::unistd::{getppid, Pid};
+/// let status = status(Pid::this()).unwrap();
+/// assert_eq!(status.ppid, getppid());
+/// ```
+pub fn status(pid: Pid) -> Result<ProcStatus> {
+    fs::read_to_string(format!("/proc/{}/status", pid))
+        .map_err(io_error)?
+        .parse()
+}
+
+/// Iterator over the threads of a process.
+///
+/// Created by [`tasks`].
+#[derive(Debug)]
+pub struct Tasks(ReadDir);
+
+impl Iterator for Tasks {
+    type Item = Result<Pid>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let entry = match self.0.next()? {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(io_error(e))),
+            };
+            // Skip anything that is not a thread ID.
+            if let Some(tid) = entry.file_name().to_str() {
+                if let Ok(tid) = tid.parse() {
+                    return Some(Ok(Pid::from_raw(tid)));
+                }
+            }
+        }
+    }
+}
+
+/// Iterate over the thread IDs of the process `pid`, from
+/// `/proc/<pid>/task`.
+///
+/// Threads may be created or exit while iterating.  Those that exist for the
+/// whole iteration are returned, the others may or may not be.
+pub fn tasks(pid: Pid) -> Result<Tasks> {
+    fs::read_dir(format!("/proc/{}/task", pid))
+        .map(Tasks)
+        .map_err(io_error)
+}
@@ -1,8 +1,17 @@
 use crate::errno::Errno;
+#[cfg(feature = "signal")]
+use crate::sys::signal::SigSet;
+#[cfg(all(
+    target_os = "linux",
+    target_pointer_width = "64",
+    feature = "signal"
+))]
+use crate::sys::time::TimeSpec;
 use crate::Result;
 use libc::{self, c_int};
+use std::collections::HashMap;
 use std::mem;
-use std::os::unix::io::{AsFd, AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
 
 libc_bitflags!(
     pub struct EpollFlags: c_int {
@@ -17,9 +26,16 @@ libc_bitflags!(
         EPOLLERR;
         EPOLLHUP;
         EPOLLRDHUP;
+        /// Wake up only one of the epoll instances waiting on the same file
+        /// descriptor, to avoid thundering herds.  Only valid with
+        /// [`Epoll::add`].
         EPOLLEXCLUSIVE;
+        /// Keep the system from suspending while this event is pending or
+        /// being processed.  Requires `CAP_BLOCK_SUSPEND`.
         #[cfg(not(target_arch = "mips"))]
         EPOLLWAKEUP;
+        /// Disable the file descriptor after one event has been reported.  Use
+        /// [`Epoll::rearm`] to enable it again.
         EPOLLONESHOT;
         EPOLLET;
     }
@@ -156,6 +172,80 @@ impl Epoll {
 
         Errno::result(res).map(|r| r as usize)
     }
+    /// Like [`Epoll::wait`], but atomically replaces the signal mask of the calling thread with
+    /// `sigmask` while waiting.
+    ///
+    /// [`epoll_pwait`](https://man7.org/linux/man-pages/man2/epoll_pwait.2.html)
+    #[cfg(feature = "signal")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "signal")))]
+    pub fn pwait(
+        &self,
+        events: &mut [EpollEvent],
+        timeout: isize,
+        sigmask: Option<&SigSet>,
+    ) -> Result<usize> {
+        let res = unsafe {
+            libc::epoll_pwait(
+                self.0.as_raw_fd(),
+                events.as_mut_ptr() as *mut libc::epoll_event,
+                events.len() as c_int,
+                timeout as c_int,
+                sigmask.map_or(std::ptr::null(), |s| s.as_ref()),
+            )
+        };
+
+        Errno::result(res).map(|r| r as usize)
+    }
+    /// Like [`Epoll::pwait`], but with a nanosecond resolution timeout.  A `timeout` of `None`
+    /// blocks indefinitely.
+    ///
+    /// Requires Linux 5.11 or later.
+    ///
+    /// [`epoll_pwait2`](https://man7.org/linux/man-pages/man2/epoll_pwait2.2.html)
+    #[cfg(all(
+        target_os = "linux",
+        target_pointer_width = "64",
+        feature = "signal"
+    ))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "signal")))]
+    pub fn pwait2(
+        &self,
+        events: &mut [EpollEvent],
+        timeout: Option<TimeSpec>,
+        sigmask: Option<&SigSet>,
+    ) -> Result<usize> {
+        // The kernel's sigset_t is smaller than libc's.
+        #[cfg(any(target_arch = "mips", target_arch = "mips64"))]
+        const KERNEL_SIGSET_SIZE: usize = 16;
+        #[cfg(not(any(target_arch = "mips", target_arch = "mips64")))]
+        const KERNEL_SIGSET_SIZE: usize = 8;
+
+        let res = unsafe {
+            libc::syscall(
+                libc::SYS_epoll_pwait2,
+                self.0.as_raw_fd(),
+                events.as_mut_ptr() as *mut libc::epoll_event,
+                events.len() as c_int,
+                timeout.as_ref().map_or(std::ptr::null(), |t| {
+                    t.as_ref() as *const libc::timespec
+                }),
+                sigmask.map_or(std::ptr::null(), |s| {
+                    s.as_ref() as *const libc::sigset_t
+                }),
+                KERNEL_SIGSET_SIZE,
+            )
+        };
+
+        Errno::result(res).map(|r| r as usize)
+    }
+    /// Re-enables a file descriptor that was registered with [`EpollFlags::EPOLLONESHOT`] and has
+    /// since reported an event, replacing its settings with `event`.
+    ///
+    /// [`epoll_ctl`](https://man7.org/linux/man-pages/man2/epoll_ctl.2.html) with `EPOLL_CTL_MOD`.
+    pub fn rearm<Fd: AsFd>(&self, fd: Fd, mut event: EpollEvent) -> Result<()> {
+        event.event.events |= EpollFlags::EPOLLONESHOT.bits() as u32;
+        self.epoll_ctl(EpollOp::EpollCtlMod, fd, &mut event)
+    }
     /// This system call is used to add, modify, or remove entries in the interest list of the epoll
     /// instance referred to by `self`. It requests that the operation `op` be performed for the
     /// target file descriptor, `fd`.
@@ -188,6 +278,166 @@ impl Epoll {
     }
 }
 
+/// An [`Epoll`] that associates a token of type `T` with each registered file descriptor.
+///
+/// Unlike [`EpollEvent::data`], tokens can be any type, so there is no need to pack indices or
+/// pointers into a `u64`.
+///
+/// The registry does not own the registered file descriptors.  Deregister a
+/// file descriptor with [`delete`](Self::delete) before closing it: epoll
+/// keeps reporting events for the underlying file while another descriptor
+/// refers to it, and a new file that reuses the number can't be added until
+/// the stale registration is deleted.
+///
+/// ```
+/// # use nix::sys::{epoll::{EpollRegistry, EpollEvent, EpollFlags, EpollCreateFlags}, eventfd::{eventfd, EfdFlags}};
+/// # use nix::unistd::write;
+/// # use std::os::unix::io::AsRawFd;
+/// # fn main() -> nix::Result<()> {
+/// #[derive(Debug, PartialEq)]
+/// enum Source { Wakeup }
+///
+/// let mut registry = EpollRegistry::new(EpollCreateFlags::empty())?;
+/// let eventfd = eventfd(0, EfdFlags::empty())?;
+/// registry.add(&eventfd, EpollFlags::EPOLLIN, Source::Wakeup)?;
+///
+/// write(eventfd.as_raw_fd(), &1u64.to_ne_bytes())?;
+/// let mut events = [EpollEvent::empty(); 4];
+/// let ready: Vec<_> = registry.wait(&mut events, 100)?.collect();
+/// assert_eq!(ready, [(&Source::Wakeup, EpollFlags::EPOLLIN)]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct EpollRegistry<T> {
+    epoll: Epoll,
+    tokens: Vec<Option<T>>,
+    free: Vec<usize>,
+    slots: HashMap<RawFd, usize>,
+}
+
+impl<T> EpollRegistry<T> {
+    /// Creates a new epoll instance with no registrations.
+    pub fn new(flags: EpollCreateFlags) -> Result<Self> {
+        Ok(EpollRegistry {
+            epoll: Epoll::new(flags)?,
+            tokens: Vec::new(),
+            free: Vec::new(),
+            slots: HashMap::new(),
+        })
+    }
+
+    /// Returns the underlying [`Epoll`].
+    pub fn epoll(&self) -> &Epoll {
+        &self.epoll
+    }
+
+    /// Registers `fd` for the events in `flags`, to be reported with `token`.
+    ///
+    /// Returns `EEXIST` if `fd` is already registered.
+    pub fn add<Fd: AsFd>(
+        &mut self,
+        fd: Fd,
+        flags: EpollFlags,
+        token: T,
+    ) -> Result<()> {
+        let raw_fd = fd.as_fd().as_raw_fd();
+        if self.slots.contains_key(&raw_fd) {
+            return Err(Errno::EEXIST);
+        }
+        let slot = self.free.pop().unwrap_or(self.tokens.len());
+        self.epoll
+            .add(fd, EpollEvent::new(flags, slot as u64))
+            .map_err(|e| {
+                if slot < self.tokens.len() {
+                    self.free.push(slot);
+                }
+                e
+            })?;
+        if slot == self.tokens.len() {
+            self.tokens.push(Some(token));
+        } else {
+            self.tokens[slot] = Some(token);
+        }
+        self.slots.insert(raw_fd, slot);
+        Ok(())
+    }
+
+    /// Changes the events `fd` is registered for, keeping its token.
+    pub fn modify<Fd: AsFd>(&self, fd: Fd, flags: EpollFlags) -> Result<()> {
+        let slot = self.slot(&fd)?;
+        self.epoll
+            .modify(fd, &mut EpollEvent::new(flags, slot as u64))
+    }
+
+    /// Re-enables `fd` after it has reported an event while registered with
+    /// [`EpollFlags::EPOLLONESHOT`].
+    pub fn rearm<Fd: AsFd>(&self, fd: Fd, flags: EpollFlags) -> Result<()> {
+        let slot = self.slot(&fd)?;
+        self.epoll.rearm(fd, EpollEvent::new(flags, slot as u64))
+    }
+
+    /// Deregisters `fd`, returning its token.
+    pub fn delete<Fd: AsFd>(&mut self, fd: Fd) -> Result<T> {
+        let raw_fd = fd.as_fd().as_raw_fd();
+        let slot = self.slot(&fd)?;
+        self.epoll.delete(fd)?;
+        self.slots.remove(&raw_fd);
+        self.free.push(slot);
+        Ok(self.tokens[slot].take().unwrap())
+    }
+
+    /// Waits for events as with [`Epoll::wait`], using `events` as the buffer, and returns the
+    /// token and flags of each ready file descriptor.
+    pub fn wait<'a>(
+        &'a self,
+        events: &'a mut [EpollEvent],
+        timeout: isize,
+    ) -> Result<impl Iterator<Item = (&'a T, EpollFlags)> + 'a> {
+        let n = self.epoll.wait(events, timeout)?;
+        Ok(self.tokens_for(&events[..n]))
+    }
+
+    /// Waits for events as with [`Epoll::pwait`], and returns the token and flags of each ready
+    /// file descriptor.
+    #[cfg(feature = "signal")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "signal")))]
+    pub fn pwait<'a>(
+        &'a self,
+        events: &'a mut [EpollEvent],
+        timeout: isize,
+        sigmask: Option<&SigSet>,
+    ) -> Result<impl Iterator<Item = (&'a T, EpollFlags)> + 'a> {
+        let n = self.epoll.pwait(events, timeout, sigmask)?;
+        Ok(self.tokens_for(&events[..n]))
+    }
+
+    fn slot<Fd: AsFd>(&self, fd: &Fd) -> Result<usize> {
+        self.slots
+            .get(&fd.as_fd().as_raw_fd())
+            .copied()
+            .ok_or(Errno::ENOENT)
+    }
+
+    fn tokens_for<'a>(
+        &'a self,
+        events: &'a [EpollEvent],
+    ) -> impl Iterator<Item = (&'a T, EpollFlags)> + 'a {
+        events.iter().filter_map(move |event| {
+            self.tokens
+                .get(event.data() as usize)
+                .and_then(Option::as_ref)
+                .map(|token| (token, event.events()))
+        })
+    }
+}
+
+impl<T> AsFd for EpollRegistry<T> {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.epoll.0.as_fd()
+    }
+}
+
 #[deprecated(since = "0.27.0", note = "Use Epoll::new() instead")]
 #[inline]
 pub fn epoll_create() -> Result<RawFd> {
@@ -1,7 +1,14 @@
 use crate::errno::Errno;
 use crate::Result;
-use std::os::unix::io::{FromRawFd, OwnedFd};
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "linux"
+)))]
+use std::sync::{Mutex, PoisonError};
 
+#[cfg(any(target_os = "android", target_os = "freebsd", target_os = "linux"))]
 libc_bitflags! {
     pub struct EfdFlags: libc::c_int {
         EFD_CLOEXEC; // Since Linux 2.6.27
@@ -10,8 +17,385 @@ libc_bitflags! {
     }
 }
 
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "linux"
+)))]
+bitflags::bitflags! {
+    /// Options of an emulated [`EventFd`].
+    pub struct EfdFlags: libc::c_int {
+        /// Set the close-on-exec flag on the file descriptors.
+        const EFD_CLOEXEC = libc::O_CLOEXEC;
+        /// Return `EAGAIN` instead of blocking in [`EventFd::read`].
+        const EFD_NONBLOCK = libc::O_NONBLOCK;
+        /// Decrement the counter by one on each read.
+        const EFD_SEMAPHORE = 0x1;
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "freebsd", target_os = "linux"))]
 pub fn eventfd(initval: libc::c_uint, flags: EfdFlags) -> Result<OwnedFd> {
     let res = unsafe { libc::eventfd(initval, flags.bits()) };
 
     Errno::result(res).map(|r| unsafe { OwnedFd::from_raw_fd(r) })
 }
+
+/// A 64-bit counter that can be waited on, to wake up threads or event
+/// loops.
+///
+/// Writing adds to the counter, which becomes readable once it is not zero.
+/// Reading returns the counter and resets it to zero, or with
+/// `EFD_SEMAPHORE` returns `1` and decrements it.
+///
+/// On Linux, Android and FreeBSD this is an `eventfd(2)`.  Elsewhere it is
+/// emulated with a pipe, whose read end is returned by [`as_fd`](AsFd::as_fd) to wait
+/// for readability with `poll` or `kqueue`.  The emulated counter is only
+/// shared by the threads of the process, not across `fork`.
+///
+/// # Example
+///
+/// ```
+/// # use nix::sys::eventfd::EventFd;
+/// # use std::sync::Arc;
+/// let efd = Arc::new(EventFd::new().unwrap());
+/// let waker = Arc::clone(&efd);
+/// std::thread::spawn(move || waker.arm().unwrap());
+/// assert_eq!(efd.read().unwrap(), 1);
+/// ```
+#[derive(Debug)]
+pub struct EventFd {
+    fd: OwnedFd,
+    #[cfg(not(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "linux"
+    )))]
+    emulation: Emulation,
+}
+
+/// The state of an emulated [`EventFd`], whose pipe holds a byte exactly
+/// while the counter is not zero.
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "linux"
+)))]
+#[derive(Debug)]
+struct Emulation {
+    write_end: OwnedFd,
+    count: Mutex<u64>,
+    flags: EfdFlags,
+}
+
+impl EventFd {
+    /// Creates an `EventFd` with a counter of `0` whose file descriptor is
+    /// closed on `execve`.
+    pub fn new() -> Result<Self> {
+        Self::from_value_and_flags(0, EfdFlags::EFD_CLOEXEC)
+    }
+
+    /// Creates an `EventFd` with a counter of `0`.
+    pub fn from_flags(flags: EfdFlags) -> Result<Self> {
+        Self::from_value_and_flags(0, flags)
+    }
+
+    /// Creates an `EventFd` with a counter of `initval`.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "linux"
+    ))]
+    pub fn from_value_and_flags(initval: u32, flags: EfdFlags) -> Result<Self> {
+        eventfd(initval, flags).map(|fd| EventFd { fd })
+    }
+
+    /// Creates an `EventFd` with a counter of `initval`.
+    #[cfg(not(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "linux"
+    )))]
+    pub fn from_value_and_flags(initval: u32, flags: EfdFlags) -> Result<Self> {
+        let (read_end, write_end) = pipe(flags)?;
+        let efd = EventFd {
+            fd: read_end,
+            emulation: Emulation {
+                write_end,
+                count: Mutex::new(0),
+                flags,
+            },
+        };
+        efd.write(initval.into())?;
+        Ok(efd)
+    }
+
+    /// Adds `1` to the counter, making the `EventFd` readable.
+    pub fn arm(&self) -> Result<()> {
+        self.write(1)
+    }
+
+    /// Resets the counter to `0` without blocking, and returns the value it
+    /// had.
+    ///
+    /// As with [`try_read`](Self::try_read), a thread reading the `EventFd`
+    /// at the same time can make this block without `EFD_NONBLOCK`.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "linux"
+    ))]
+    pub fn defuse(&self) -> Result<u64> {
+        let mut total = 0;
+        while let Some(value) = self.try_read()? {
+            total += value;
+            // Only a semaphore is drained one at a time, and it always
+            // reads as `1`.
+            if value != 1 {
+                break;
+            }
+        }
+        Ok(total)
+    }
+
+    /// Resets the counter to `0` without blocking, and returns the value it
+    /// had.
+    #[cfg(not(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "linux"
+    )))]
+    pub fn defuse(&self) -> Result<u64> {
+        let mut total = 0;
+        while let Some(value) = self.try_read()? {
+            total += value;
+        }
+        Ok(total)
+    }
+
+    /// Adds `value` to the counter.
+    ///
+    /// Returns `EINVAL` for `u64::MAX`.  If the counter would exceed
+    /// `u64::MAX - 1`, this blocks until it is read, or returns `EAGAIN`
+    /// with `EFD_NONBLOCK`.  The emulation always returns `EAGAIN` then.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "linux"
+    ))]
+    pub fn write(&self, value: u64) -> Result<()> {
+        let buf = value.to_ne_bytes();
+        let res = unsafe {
+            libc::write(self.fd.as_raw_fd(), buf.as_ptr().cast(), buf.len())
+        };
+        Errno::result(res).map(drop)
+    }
+
+    /// Adds `value` to the counter.
+    ///
+    /// Returns `EINVAL` for `u64::MAX`.  If the counter would exceed
+    /// `u64::MAX - 1`, this blocks until it is read, or returns `EAGAIN`
+    /// with `EFD_NONBLOCK`.  The emulation always returns `EAGAIN` then.
+    #[cfg(not(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "linux"
+    )))]
+    pub fn write(&self, value: u64) -> Result<()> {
+        if value == u64::MAX {
+            return Err(Errno::EINVAL);
+        }
+        let mut count = self
+            .emulation
+            .count
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if u64::MAX - 1 - *count < value {
+            return Err(Errno::EAGAIN);
+        }
+        if *count == 0 && value > 0 {
+            let res = unsafe {
+                libc::write(
+                    self.emulation.write_end.as_raw_fd(),
+                    [0u8].as_ptr().cast(),
+                    1,
+                )
+            };
+            Errno::result(res)?;
+        }
+        *count += value;
+        Ok(())
+    }
+
+    /// Reads the counter, blocking while it is `0` unless the `EventFd` was
+    /// created with `EFD_NONBLOCK`, in which case this returns `EAGAIN`.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "linux"
+    ))]
+    pub fn read(&self) -> Result<u64> {
+        let mut buf = [0u8; 8];
+        let res = unsafe {
+            libc::read(self.fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len())
+        };
+        Errno::result(res).map(|_| u64::from_ne_bytes(buf))
+    }
+
+    /// Reads the counter, blocking while it is `0` unless the `EventFd` was
+    /// created with `EFD_NONBLOCK`, in which case this returns `EAGAIN`.
+    #[cfg(not(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "linux"
+    )))]
+    pub fn read(&self) -> Result<u64> {
+        loop {
+            if let Some(value) = self.try_read()? {
+                return Ok(value);
+            }
+            if self.emulation.flags.contains(EfdFlags::EFD_NONBLOCK) {
+                return Err(Errno::EAGAIN);
+            }
+            wait_readable(self.fd.as_raw_fd(), -1)?;
+        }
+    }
+
+    /// Reads the counter, or returns `None` if it is `0`.
+    ///
+    /// Without `EFD_NONBLOCK`, a thread reading the `EventFd` at the same
+    /// time can make this block.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "linux"
+    ))]
+    pub fn try_read(&self) -> Result<Option<u64>> {
+        if !wait_readable(self.fd.as_raw_fd(), 0)? {
+            return Ok(None);
+        }
+        match self.read() {
+            Err(Errno::EAGAIN) => Ok(None),
+            res => res.map(Some),
+        }
+    }
+
+    /// Reads the counter, or returns `None` if it is `0`.
+    #[cfg(not(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "linux"
+    )))]
+    pub fn try_read(&self) -> Result<Option<u64>> {
+        let mut count = self
+            .emulation
+            .count
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if *count == 0 {
+            return Ok(None);
+        }
+        let value = if self.emulation.flags.contains(EfdFlags::EFD_SEMAPHORE) {
+            1
+        } else {
+            *count
+        };
+        if *count == value {
+            let mut buf = [0u8];
+            let res = unsafe {
+                libc::read(self.fd.as_raw_fd(), buf.as_mut_ptr().cast(), 1)
+            };
+            Errno::result(res)?;
+        }
+        *count -= value;
+        Ok(Some(value))
+    }
+}
+
+/// Creates the pipe of an emulated [`EventFd`].
+///
+/// Both ends are non-blocking, so that blocking reads wait with `poll` and
+/// the lock on the counter is never held while blocked.
+#[cfg(any(
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn pipe(flags: EfdFlags) -> Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [0; 2];
+    let oflags = libc::O_NONBLOCK | (flags & EfdFlags::EFD_CLOEXEC).bits();
+    Errno::result(unsafe { libc::pipe2(fds.as_mut_ptr(), oflags) })?;
+    unsafe { Ok((OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1]))) }
+}
+
+/// Creates the pipe of an emulated [`EventFd`].
+///
+/// Both ends are non-blocking, so that blocking reads wait with `poll` and
+/// the lock on the counter is never held while blocked.  Without `pipe2`,
+/// the flags can only be set after the pipe was created.
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+fn pipe(flags: EfdFlags) -> Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [0; 2];
+    Errno::result(unsafe { libc::pipe(fds.as_mut_ptr()) })?;
+    let fds =
+        unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+    for fd in [&fds.0, &fds.1] {
+        let fd = fd.as_raw_fd();
+        Errno::result(unsafe {
+            libc::fcntl(fd, libc::F_SETFL, libc::O_NONBLOCK)
+        })?;
+        if flags.contains(EfdFlags::EFD_CLOEXEC) {
+            Errno::result(unsafe {
+                libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC)
+            })?;
+        }
+    }
+    Ok(fds)
+}
+
+/// Waits up to `timeout` milliseconds for `fd` to become readable, and
+/// returns whether it is.
+fn wait_readable(fd: RawFd, timeout: libc::c_int) -> Result<bool> {
+    let mut pollfd = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let res = unsafe { libc::poll(&mut pollfd, 1, timeout) };
+    Errno::result(res).map(|n| n > 0)
+}
+
+impl AsFd for EventFd {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl AsRawFd for EventFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "freebsd", target_os = "linux"))]
+impl FromRawFd for EventFd {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        EventFd {
+            fd: OwnedFd::from_raw_fd(fd),
+        }
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "freebsd", target_os = "linux"))]
+impl From<OwnedFd> for EventFd {
+    fn from(fd: OwnedFd) -> Self {
+        EventFd { fd }
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "freebsd", target_os = "linux"))]
+impl From<EventFd> for OwnedFd {
+    fn from(efd: EventFd) -> Self {
+        efd.fd
+    }
+}
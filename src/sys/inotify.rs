@@ -28,11 +28,11 @@ use crate::unistd::read;
 use crate::NixPath;
 use crate::Result;
 use cfg_if::cfg_if;
-use libc::{c_char, c_int};
-use std::ffi::{CStr, OsStr, OsString};
-use std::mem::{size_of, MaybeUninit};
+use libc::c_int;
+use std::ffi::{OsStr, OsString};
+use std::mem::size_of;
 use std::os::unix::ffi::OsStrExt;
-use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
 use std::ptr;
 
 libc_bitflags! {
@@ -189,49 +189,105 @@ impl Inotify {
     /// Returns as many events as available. If the call was non blocking and no
     /// events could be read then the EAGAIN error is returned.
     pub fn read_events(&self) -> Result<Vec<InotifyEvent>> {
-        let header_size = size_of::<libc::inotify_event>();
         const BUFSIZ: usize = 4096;
         let mut buffer = [0u8; BUFSIZ];
-        let mut events = Vec::new();
-        let mut offset = 0;
-
-        let nread = read(self.fd.as_raw_fd(), &mut buffer)?;
-
-        while (nread - offset) >= header_size {
-            let event = unsafe {
-                let mut event = MaybeUninit::<libc::inotify_event>::uninit();
-                ptr::copy_nonoverlapping(
-                    buffer.as_ptr().add(offset),
-                    event.as_mut_ptr() as *mut u8,
-                    (BUFSIZ - offset).min(header_size),
-                );
-                event.assume_init()
-            };
 
-            let name = match event.len {
-                0 => None,
-                _ => {
-                    let ptr = unsafe {
-                        buffer.as_ptr().add(offset + header_size)
-                            as *const c_char
-                    };
-                    let cstr = unsafe { CStr::from_ptr(ptr) };
+        let events = self.read_events_into(&mut buffer)?;
+        Ok(events.map(InotifyEvent::from).collect())
+    }
 
-                    Some(OsStr::from_bytes(cstr.to_bytes()).to_owned())
-                }
-            };
+    /// Reads events into a caller-supplied buffer, without allocating.
+    ///
+    /// The returned iterator borrows the event names from `buffer`, so the
+    /// same buffer can be reused for every call.  The kernel only ever returns
+    /// whole events; if `buffer` is too small to hold even a single event,
+    /// `EINVAL` is returned.  A buffer of
+    /// `size_of::<libc::inotify_event>() + NAME_MAX + 1` bytes is always large
+    /// enough.
+    ///
+    /// Like [`read_events`](Self::read_events), this blocks unless
+    /// `IN_NONBLOCK` was set at initialization.
+    pub fn read_events_into<'a>(
+        &self,
+        buffer: &'a mut [u8],
+    ) -> Result<InotifyEventIter<'a>> {
+        let nread = read(self.fd.as_raw_fd(), buffer)?;
+
+        Ok(InotifyEventIter {
+            buffer: &buffer[..nread],
+            offset: 0,
+        })
+    }
+}
+
+/// A single inotify event, borrowed from the buffer passed to
+/// [`Inotify::read_events_into`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct InotifyEventRef<'a> {
+    /// Watch descriptor of the watch this event comes from.
+    pub wd: WatchDescriptor,
+    /// Event mask describing the exact event that occurred.
+    pub mask: AddWatchFlags,
+    /// Connects related `IN_MOVED_FROM` and `IN_MOVED_TO` events.
+    pub cookie: u32,
+    /// Name of the file inside the watched directory, if any.
+    pub name: Option<&'a OsStr>,
+}
+
+impl<'a> From<InotifyEventRef<'a>> for InotifyEvent {
+    fn from(event: InotifyEventRef<'a>) -> Self {
+        InotifyEvent {
+            wd: event.wd,
+            mask: event.mask,
+            cookie: event.cookie,
+            name: event.name.map(OsStr::to_owned),
+        }
+    }
+}
 
-            events.push(InotifyEvent {
-                wd: WatchDescriptor { wd: event.wd },
-                mask: AddWatchFlags::from_bits_truncate(event.mask),
-                cookie: event.cookie,
-                name,
-            });
+/// Iterator over the events returned by [`Inotify::read_events_into`].
+#[derive(Debug)]
+pub struct InotifyEventIter<'a> {
+    buffer: &'a [u8],
+    offset: usize,
+}
 
-            offset += header_size + event.len as usize;
+impl<'a> Iterator for InotifyEventIter<'a> {
+    type Item = InotifyEventRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let header_size = size_of::<libc::inotify_event>();
+        if self.buffer.len() - self.offset < header_size {
+            return None;
         }
 
-        Ok(events)
+        // The buffer is not necessarily aligned for `inotify_event`.
+        let event = unsafe {
+            ptr::read_unaligned(
+                self.buffer.as_ptr().add(self.offset)
+                    as *const libc::inotify_event,
+            )
+        };
+        let start = self.offset + header_size;
+        let end = (start + event.len as usize).min(self.buffer.len());
+        self.offset = end;
+
+        let name = match event.len {
+            0 => None,
+            _ => {
+                // The name is padded with NUL bytes to an aligned length.
+                let raw = &self.buffer[start..end];
+                let len = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+                Some(OsStr::from_bytes(&raw[..len]))
+            }
+        };
+
+        Some(InotifyEventRef {
+            wd: WatchDescriptor { wd: event.wd },
+            mask: AddWatchFlags::from_bits_truncate(event.mask),
+            cookie: event.cookie,
+            name,
+        })
     }
 }
 
@@ -240,3 +296,409 @@ impl FromRawFd for Inotify {
         Inotify { fd: OwnedFd::from_raw_fd(fd) }
     }
 }
+
+impl AsFd for Inotify {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl AsRawFd for Inotify {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl From<OwnedFd> for Inotify {
+    fn from(fd: OwnedFd) -> Self {
+        Inotify { fd }
+    }
+}
+
+impl From<Inotify> for OwnedFd {
+    fn from(inotify: Inotify) -> Self {
+        inotify.fd
+    }
+}
+
+#[cfg(feature = "dir")]
+pub use self::recursive::{RecursiveEvent, RecursiveWatcher};
+
+#[cfg(feature = "dir")]
+mod recursive {
+    use super::{
+        AddWatchFlags, InitFlags, Inotify, InotifyEventRef, WatchDescriptor,
+    };
+    use crate::dir::{Dir, Type};
+    use crate::errno::Errno;
+    use crate::fcntl::{AtFlags, OFlag};
+    use crate::sys::stat::{fstatat, Mode, SFlag};
+    use crate::Result;
+    use std::collections::HashMap;
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+    use std::path::{Path, PathBuf};
+
+    /// An event reported by a [`RecursiveWatcher`].
+    #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+    pub struct RecursiveEvent {
+        /// Path of the file or directory the event refers to.
+        ///
+        /// For `IN_Q_OVERFLOW`, this is the root of the watched tree.
+        pub path: PathBuf,
+        /// Event mask describing the exact event that occurred.
+        pub mask: AddWatchFlags,
+        /// Connects related `IN_MOVED_FROM` and `IN_MOVED_TO` events.
+        pub cookie: u32,
+    }
+
+    /// Watches a directory and all of its subdirectories.
+    ///
+    /// Inotify watches are not recursive, so this keeps one watch per
+    /// directory in the tree up to date:
+    ///
+    /// * Directories that are created in, or moved into, the tree are
+    ///   watched, together with everything below them.
+    /// * Directories that are moved within the tree keep their watches, with
+    ///   `IN_MOVED_FROM` and `IN_MOVED_TO` paired by their cookie.
+    /// * Directories that are moved out of the tree stop being watched.
+    /// * When the event queue overflows, the whole tree is rescanned and an
+    ///   `IN_Q_OVERFLOW` event is reported so that the caller can rescan too.
+    ///
+    /// Note that events for files created in a new directory before its watch
+    /// was added are not reported.
+    ///
+    /// Directories that cannot be watched or listed because of `EACCES` or
+    /// `EPERM` are skipped, along with everything below them.  If any other
+    /// error occurs while updating the watches, the events of the current
+    /// batch are still returned, and the error is returned by the next call
+    /// to [`read_events`](RecursiveWatcher::read_events).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use nix::sys::inotify::{AddWatchFlags, InitFlags, RecursiveWatcher};
+    /// let mut watcher = RecursiveWatcher::new(
+    ///     "test",
+    ///     AddWatchFlags::IN_CREATE | AddWatchFlags::IN_DELETE,
+    ///     InitFlags::empty(),
+    /// ).unwrap();
+    ///
+    /// loop {
+    ///     for event in watcher.read_events().unwrap() {
+    ///         println!("{:?} {}", event.mask, event.path.display());
+    ///     }
+    /// }
+    /// ```
+    #[derive(Debug)]
+    pub struct RecursiveWatcher {
+        inotify: Inotify,
+        root: PathBuf,
+        mask: AddWatchFlags,
+        paths: HashMap<WatchDescriptor, PathBuf>,
+        wds: HashMap<PathBuf, WatchDescriptor>,
+        /// Directories that were moved away, by cookie, awaiting the matching
+        /// `IN_MOVED_TO`.
+        moved_from: HashMap<u32, PathBuf>,
+        buffer: Vec<u8>,
+        /// Error hit while handling the last batch, to be reported next.
+        error: Option<Errno>,
+    }
+
+    impl RecursiveWatcher {
+        /// Starts watching `root` and every directory below it for the events
+        /// in `mask`.
+        pub fn new<P: AsRef<Path>>(
+            root: P,
+            mask: AddWatchFlags,
+            flags: InitFlags,
+        ) -> Result<RecursiveWatcher> {
+            const BUFSIZ: usize = 16384;
+
+            let mut watcher = RecursiveWatcher {
+                inotify: Inotify::init(flags)?,
+                root: root.as_ref().to_owned(),
+                mask,
+                paths: HashMap::new(),
+                wds: HashMap::new(),
+                moved_from: HashMap::new(),
+                buffer: vec![0; BUFSIZ],
+                error: None,
+            };
+            let root = watcher.root.clone();
+            // Unlike its subdirectories, the root must be watchable.
+            watcher.inotify.add_watch(&root, watcher.watch_mask())?;
+            watcher.watch_tree(&root)?;
+            if watcher.wds.is_empty() {
+                // The root itself vanished or is not a directory.
+                return Err(Errno::ENOTDIR);
+            }
+            Ok(watcher)
+        }
+
+        /// Returns the paths of all currently watched directories.
+        pub fn watched_dirs(&self) -> impl Iterator<Item = &Path> {
+            self.wds.keys().map(PathBuf::as_path)
+        }
+
+        /// Reads the next batch of events, updating the set of watched
+        /// directories as needed.
+        ///
+        /// Like [`Inotify::read_events`], this blocks unless `IN_NONBLOCK`
+        /// was set at initialization.
+        pub fn read_events(&mut self) -> Result<Vec<RecursiveEvent>> {
+            if let Some(e) = self.error.take() {
+                return Err(e);
+            }
+            let mut buffer = std::mem::take(&mut self.buffer);
+            let res = self.read_events_with(&mut buffer);
+            self.buffer = buffer;
+            res
+        }
+
+        fn read_events_with(
+            &mut self,
+            buffer: &mut [u8],
+        ) -> Result<Vec<RecursiveEvent>> {
+            let mut events = Vec::new();
+            for event in self.inotify.read_events_into(buffer)? {
+                if let Some(event) = self.handle(event) {
+                    events.push(event);
+                }
+            }
+            Ok(events)
+        }
+
+        /// Keeps the first error of a batch, so that it can be reported after
+        /// the batch's events have been returned.
+        fn defer(&mut self, res: Result<()>) {
+            if let Err(e) = res {
+                self.error.get_or_insert(e);
+            }
+        }
+
+        fn handle(&mut self, event: InotifyEventRef) -> Option<RecursiveEvent> {
+            if event.mask.contains(AddWatchFlags::IN_Q_OVERFLOW) {
+                let res = self.rescan();
+                self.defer(res);
+                return Some(RecursiveEvent {
+                    path: self.root.clone(),
+                    mask: event.mask,
+                    cookie: 0,
+                });
+            }
+
+            if !event.mask.contains(AddWatchFlags::IN_MOVED_TO) {
+                self.forget_moved_from();
+            }
+
+            let dir = match self.paths.get(&event.wd) {
+                Some(dir) => dir.clone(),
+                // Late event for a watch that has already been removed.
+                None => return None,
+            };
+            let path = match event.name {
+                Some(name) => dir.join(name),
+                None => dir,
+            };
+            let is_dir = event.mask.contains(AddWatchFlags::IN_ISDIR);
+
+            if event.mask.contains(AddWatchFlags::IN_IGNORED) {
+                self.forget_wd(event.wd);
+            } else if is_dir && event.mask.contains(AddWatchFlags::IN_CREATE) {
+                let res = self.watch_tree(&path);
+                self.defer(res);
+            } else if is_dir
+                && event.mask.contains(AddWatchFlags::IN_MOVED_FROM)
+            {
+                self.moved_from.insert(event.cookie, path.clone());
+            } else if is_dir && event.mask.contains(AddWatchFlags::IN_MOVED_TO)
+            {
+                match self.moved_from.remove(&event.cookie) {
+                    Some(from) => self.rename_tree(&from, &path),
+                    None => {
+                        let res = self.watch_tree(&path);
+                        self.defer(res);
+                    }
+                }
+            }
+
+            let reported = self.mask
+                | AddWatchFlags::IN_IGNORED
+                | AddWatchFlags::IN_UNMOUNT;
+            if event.mask.intersects(reported) {
+                Some(RecursiveEvent {
+                    path,
+                    mask: event.mask,
+                    cookie: event.cookie,
+                })
+            } else {
+                None
+            }
+        }
+
+        fn watch_mask(&self) -> AddWatchFlags {
+            self.mask
+                | AddWatchFlags::IN_CREATE
+                | AddWatchFlags::IN_MOVED_FROM
+                | AddWatchFlags::IN_MOVED_TO
+                | AddWatchFlags::IN_ONLYDIR
+                | AddWatchFlags::IN_DONT_FOLLOW
+        }
+
+        /// Watches `top` and every directory below it.
+        ///
+        /// Directories that vanished or cannot be accessed are skipped.  Other
+        /// errors do not stop the walk; the first one is returned at the end.
+        fn watch_tree(&mut self, top: &Path) -> Result<()> {
+            let mask = self.watch_mask();
+            let mut error = None;
+            let mut pending = vec![top.to_owned()];
+            while let Some(path) = pending.pop() {
+                let wd = match self.inotify.add_watch(&path, mask) {
+                    Ok(wd) => wd,
+                    Err(e) if is_skippable(e) => continue,
+                    Err(e) => {
+                        error.get_or_insert(e);
+                        continue;
+                    }
+                };
+                if let Some(old) = self.paths.insert(wd, path.clone()) {
+                    self.wds.remove(&old);
+                }
+                self.wds.insert(path.clone(), wd);
+
+                let mut dir = match Dir::open(
+                    &path,
+                    OFlag::O_RDONLY | OFlag::O_DIRECTORY | OFlag::O_CLOEXEC,
+                    Mode::empty(),
+                ) {
+                    Ok(dir) => dir,
+                    Err(e) if is_skippable(e) => continue,
+                    Err(e) => {
+                        error.get_or_insert(e);
+                        continue;
+                    }
+                };
+                let dirfd = dir.as_raw_fd();
+                for entry in dir.iter() {
+                    let entry = match entry {
+                        Ok(entry) => entry,
+                        Err(e) if is_skippable(e) => break,
+                        Err(e) => {
+                            error.get_or_insert(e);
+                            break;
+                        }
+                    };
+                    let name = entry.file_name();
+                    if name.to_bytes() == b"." || name.to_bytes() == b".." {
+                        continue;
+                    }
+                    if is_directory(dirfd, entry.file_type(), name) {
+                        pending.push(
+                            path.join(OsStr::from_bytes(name.to_bytes())),
+                        );
+                    }
+                }
+            }
+            error.map_or(Ok(()), Err)
+        }
+
+        /// Updates the paths of `from` and everything below it, which were
+        /// moved to `to`.
+        fn rename_tree(&mut self, from: &Path, to: &Path) {
+            let moved: Vec<_> = self
+                .wds
+                .keys()
+                .filter(|p| p.starts_with(from))
+                .cloned()
+                .collect();
+            for old in moved {
+                let new = to.join(old.strip_prefix(from).unwrap());
+                let wd = self.wds.remove(&old).unwrap();
+                self.paths.insert(wd, new.clone());
+                self.wds.insert(new, wd);
+            }
+        }
+
+        /// Stops watching directories that were moved out of the tree.
+        fn forget_moved_from(&mut self) {
+            let moved: Vec<_> =
+                self.moved_from.drain().map(|(_, p)| p).collect();
+            for from in moved {
+                let gone: Vec<_> = self
+                    .wds
+                    .iter()
+                    .filter(|(p, _)| p.starts_with(&from))
+                    .map(|(_, wd)| *wd)
+                    .collect();
+                for wd in gone {
+                    // The watch may already be gone, which is fine.
+                    let _ = self.inotify.rm_watch(wd);
+                    self.forget_wd(wd);
+                }
+            }
+        }
+
+        fn forget_wd(&mut self, wd: WatchDescriptor) {
+            if let Some(path) = self.paths.remove(&wd) {
+                self.wds.remove(&path);
+            }
+        }
+
+        /// Rebuilds the set of watches after events have been lost.
+        fn rescan(&mut self) -> Result<()> {
+            self.moved_from.clear();
+            let old: Vec<_> = self.paths.keys().copied().collect();
+            self.paths.clear();
+            self.wds.clear();
+            let root = self.root.clone();
+            let res = self.watch_tree(&root);
+            for wd in old {
+                if !self.paths.contains_key(&wd) {
+                    let _ = self.inotify.rm_watch(wd);
+                }
+            }
+            res
+        }
+    }
+
+    /// Whether a directory that failed with `errno` should be left unwatched
+    /// rather than reported: it vanished, was replaced by a file, or is not
+    /// accessible to us.
+    fn is_skippable(errno: Errno) -> bool {
+        matches!(
+            errno,
+            Errno::ENOENT | Errno::ENOTDIR | Errno::EACCES | Errno::EPERM
+        )
+    }
+
+    fn is_directory(
+        dirfd: RawFd,
+        file_type: Option<Type>,
+        name: &std::ffi::CStr,
+    ) -> bool {
+        match file_type {
+            Some(t) => t == Type::Directory,
+            None => fstatat(dirfd, name, AtFlags::AT_SYMLINK_NOFOLLOW)
+                .map(|st| {
+                    SFlag::from_bits_truncate(st.st_mode) & SFlag::S_IFMT
+                        == SFlag::S_IFDIR
+                })
+                .unwrap_or(false),
+        }
+    }
+
+    impl AsFd for RecursiveWatcher {
+        fn as_fd(&self) -> BorrowedFd<'_> {
+            self.inotify.as_fd()
+        }
+    }
+
+    impl AsRawFd for RecursiveWatcher {
+        fn as_raw_fd(&self) -> RawFd {
+            self.inotify.as_raw_fd()
+        }
+    }
+}
@@ -1,9 +1,17 @@
 //! Interfaces for managing memory-backed files.
 
 use cfg_if::cfg_if;
-use std::os::unix::io::{FromRawFd, OwnedFd, RawFd};
+use libc::off_t;
+#[cfg(feature = "mman")]
+use std::num::NonZeroUsize;
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
 
 use crate::errno::Errno;
+use crate::fcntl::{fcntl, FcntlArg, SealFlag};
+#[cfg(feature = "mman")]
+use crate::sys::mman::{MapFlags, MmapRegion, ProtFlags};
+use crate::sys::stat::fstat;
+use crate::unistd::ftruncate;
 use crate::Result;
 use std::ffi::CStr;
 
@@ -29,9 +37,54 @@ libc_bitflags!(
         ///
         /// [`memfd_create(2)`]: https://man7.org/linux/man-pages/man2/memfd_create.2.html
         MFD_ALLOW_SEALING;
+        /// Back the file with huge pages of the default size.
+        ///
+        /// Combine with one of the `MFD_HUGE_*` flags to select another size.
+        MFD_HUGETLB;
+        /// Make use of 64KB huge pages (must be supported by the system)
+        MFD_HUGE_64KB;
+        /// Make use of 512KB huge pages (must be supported by the system)
+        MFD_HUGE_512KB;
+        /// Make use of 1MB huge pages (must be supported by the system)
+        MFD_HUGE_1MB;
+        /// Make use of 2MB huge pages (must be supported by the system)
+        MFD_HUGE_2MB;
+        /// Make use of 8MB huge pages (must be supported by the system)
+        MFD_HUGE_8MB;
+        /// Make use of 16MB huge pages (must be supported by the system)
+        MFD_HUGE_16MB;
+        /// Make use of 32MB huge pages (must be supported by the system)
+        MFD_HUGE_32MB;
+        /// Make use of 256MB huge pages (must be supported by the system)
+        MFD_HUGE_256MB;
+        /// Make use of 512MB huge pages (must be supported by the system)
+        MFD_HUGE_512MB;
+        /// Make use of 1GB huge pages (must be supported by the system)
+        MFD_HUGE_1GB;
+        /// Make use of 2GB huge pages (must be supported by the system)
+        MFD_HUGE_2GB;
+        /// Make use of 16GB huge pages (must be supported by the system)
+        MFD_HUGE_16GB;
     }
 );
 
+#[cfg(target_os = "linux")]
+impl MemFdCreateFlag {
+    /// Create the file without execute permission and seal it with
+    /// [`F_SEAL_EXEC`](SealFlag::F_SEAL_EXEC) so that it cannot be made
+    /// executable.  Implies `MFD_ALLOW_SEALING`.
+    ///
+    /// Supported since Linux 6.3.
+    pub const MFD_NOEXEC_SEAL: MemFdCreateFlag =
+        unsafe { MemFdCreateFlag::from_bits_unchecked(0x8) };
+    /// Create the file with execute permission, even if the
+    /// `vm.memfd_noexec` sysctl asks for `MFD_NOEXEC_SEAL` by default.
+    ///
+    /// Supported since Linux 6.3.
+    pub const MFD_EXEC: MemFdCreateFlag =
+        unsafe { MemFdCreateFlag::from_bits_unchecked(0x10) };
+}
+
 /// Creates an anonymous file that lives in memory, and return a file-descriptor to it.
 ///
 /// The file behaves like a regular file, and so can be modified, truncated, memory-mapped, and so on.
@@ -62,3 +115,297 @@ pub fn memfd_create(name: &CStr, flags: MemFdCreateFlag) -> Result<OwnedFd> {
 
     Errno::result(res).map(|r| unsafe { OwnedFd::from_raw_fd(r as RawFd) })
 }
+
+/// The size of the huge pages backing a file created by [`MemFdBuilder`].
+///
+/// Sizes other than the default must be supported by the CPU and configured
+/// in the kernel.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum HugePageSize {
+    /// The default huge page size of the system.
+    Default,
+    /// 64KB huge pages.
+    Size64KB,
+    /// 512KB huge pages.
+    Size512KB,
+    /// 1MB huge pages.
+    Size1MB,
+    /// 2MB huge pages.
+    Size2MB,
+    /// 8MB huge pages.
+    Size8MB,
+    /// 16MB huge pages.
+    Size16MB,
+    /// 32MB huge pages.
+    Size32MB,
+    /// 256MB huge pages.
+    Size256MB,
+    /// 512MB huge pages.
+    Size512MB,
+    /// 1GB huge pages.
+    Size1GB,
+    /// 2GB huge pages.
+    Size2GB,
+    /// 16GB huge pages.
+    Size16GB,
+}
+
+impl HugePageSize {
+    fn flag(self) -> MemFdCreateFlag {
+        match self {
+            HugePageSize::Default => MemFdCreateFlag::empty(),
+            HugePageSize::Size64KB => MemFdCreateFlag::MFD_HUGE_64KB,
+            HugePageSize::Size512KB => MemFdCreateFlag::MFD_HUGE_512KB,
+            HugePageSize::Size1MB => MemFdCreateFlag::MFD_HUGE_1MB,
+            HugePageSize::Size2MB => MemFdCreateFlag::MFD_HUGE_2MB,
+            HugePageSize::Size8MB => MemFdCreateFlag::MFD_HUGE_8MB,
+            HugePageSize::Size16MB => MemFdCreateFlag::MFD_HUGE_16MB,
+            HugePageSize::Size32MB => MemFdCreateFlag::MFD_HUGE_32MB,
+            HugePageSize::Size256MB => MemFdCreateFlag::MFD_HUGE_256MB,
+            HugePageSize::Size512MB => MemFdCreateFlag::MFD_HUGE_512MB,
+            HugePageSize::Size1GB => MemFdCreateFlag::MFD_HUGE_1GB,
+            HugePageSize::Size2GB => MemFdCreateFlag::MFD_HUGE_2GB,
+            HugePageSize::Size16GB => MemFdCreateFlag::MFD_HUGE_16GB,
+        }
+    }
+}
+
+/// Builder of a [`MemFd`].
+///
+/// By default the file descriptor is close-on-exec, the file cannot be
+/// sealed, and it is backed by normal pages.
+///
+/// # Example
+///
+/// ```
+/// # use nix::fcntl::SealFlag;
+/// # use nix::sys::memfd::MemFdBuilder;
+/// # use std::ffi::CStr;
+/// let name = CStr::from_bytes_with_nul(b"buffer\0").unwrap();
+/// let memfd = MemFdBuilder::new(name).allow_sealing(true).create().unwrap();
+/// memfd.set_len(4096).unwrap();
+/// memfd.add_seals(SealFlag::F_SEAL_GROW | SealFlag::F_SEAL_SHRINK).unwrap();
+/// assert_eq!(memfd.set_len(8192), Err(nix::errno::Errno::EPERM));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct MemFdBuilder<'a> {
+    name: &'a CStr,
+    flags: MemFdCreateFlag,
+    huge_page_size: Option<HugePageSize>,
+}
+
+impl<'a> MemFdBuilder<'a> {
+    /// Starts building a file named `name`.
+    ///
+    /// The name is only used for debugging, as the target of the file's
+    /// symbolic link in `/proc/self/fd`, and need not be unique.
+    pub fn new(name: &'a CStr) -> Self {
+        MemFdBuilder {
+            name,
+            flags: MemFdCreateFlag::MFD_CLOEXEC,
+            huge_page_size: None,
+        }
+    }
+
+    /// Whether the file descriptor is closed on `execve`.
+    pub fn cloexec(mut self, cloexec: bool) -> Self {
+        self.flags.set(MemFdCreateFlag::MFD_CLOEXEC, cloexec);
+        self
+    }
+
+    /// Whether seals can be added with [`MemFd::add_seals`].
+    pub fn allow_sealing(mut self, allow_sealing: bool) -> Self {
+        self.flags
+            .set(MemFdCreateFlag::MFD_ALLOW_SEALING, allow_sealing);
+        self
+    }
+
+    /// Back the file with huge pages of `size`.
+    ///
+    /// The file can then only be resized to multiples of the huge page size,
+    /// and not written with `write(2)`.  Creating it fails with `EINVAL` if
+    /// the size is not supported, and sealing is only supported since
+    /// Linux 4.16.
+    pub fn huge_pages(mut self, size: HugePageSize) -> Self {
+        self.huge_page_size = Some(size);
+        self
+    }
+
+    /// Whether the file is created executable, with `MFD_EXEC`, or sealed
+    /// against ever becoming executable, with `MFD_NOEXEC_SEAL`.  If not set,
+    /// the `vm.memfd_noexec` sysctl decides.
+    ///
+    /// Creating the file fails with `EINVAL` before Linux 6.3.
+    #[cfg(target_os = "linux")]
+    #[cfg_attr(docsrs, doc(cfg(all())))]
+    pub fn executable(mut self, executable: bool) -> Self {
+        self.flags.set(MemFdCreateFlag::MFD_EXEC, executable);
+        self.flags
+            .set(MemFdCreateFlag::MFD_NOEXEC_SEAL, !executable);
+        self
+    }
+
+    /// Creates the file.
+    pub fn create(self) -> Result<MemFd> {
+        let mut flags = self.flags;
+        if let Some(size) = self.huge_page_size {
+            flags |= MemFdCreateFlag::MFD_HUGETLB | size.flag();
+        }
+        memfd_create(self.name, flags).map(MemFd::from)
+    }
+}
+
+/// A memory-backed file, created with [`MemFdBuilder`] or received from
+/// another process.
+#[derive(Debug)]
+pub struct MemFd {
+    fd: OwnedFd,
+}
+
+impl MemFd {
+    /// Sets the size of the file, filling it with zeros if it grows.
+    pub fn set_len(&self, len: u64) -> Result<()> {
+        let len = off_t::try_from(len).map_err(|_| Errno::EFBIG)?;
+        ftruncate(&self.fd, len)
+    }
+
+    /// Returns the size of the file.
+    pub fn size(&self) -> Result<u64> {
+        fstat(self.fd.as_raw_fd()).map(|stat| stat.st_size as u64)
+    }
+
+    /// Adds `seals` to the seals of the file.
+    ///
+    /// Seals can never be removed.  Returns `EPERM` if the file was created
+    /// without `MFD_ALLOW_SEALING` or is sealed with `F_SEAL_SEAL`, and
+    /// `EBUSY` for `F_SEAL_WRITE` while the file has writable shared
+    /// mappings.
+    pub fn add_seals(&self, seals: SealFlag) -> Result<()> {
+        fcntl(self.fd.as_raw_fd(), FcntlArg::F_ADD_SEALS(seals)).map(drop)
+    }
+
+    /// Returns the seals of the file.
+    pub fn seals(&self) -> Result<SealFlag> {
+        fcntl(self.fd.as_raw_fd(), FcntlArg::F_GET_SEALS)
+            .map(|bits| unsafe { SealFlag::from_bits_unchecked(bits) })
+    }
+
+    /// Maps the whole file privately into memory.
+    ///
+    /// Mapping a file is only safe while no one can modify or shrink it, so
+    /// this returns `EPERM` unless the file is sealed with both
+    /// `F_SEAL_WRITE` and `F_SEAL_SHRINK`, and `EINVAL` if it is empty.
+    /// Writes to the mapping, if `prot` contains `PROT_WRITE`, do not reach
+    /// the file.
+    #[cfg(feature = "mman")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mman")))]
+    pub fn map(&self, prot: ProtFlags) -> Result<MmapRegion> {
+        if !self
+            .seals()?
+            .contains(SealFlag::F_SEAL_WRITE | SealFlag::F_SEAL_SHRINK)
+        {
+            return Err(Errno::EPERM);
+        }
+        let len = usize::try_from(self.size()?).map_err(|_| Errno::ENOMEM)?;
+        let len = NonZeroUsize::new(len).ok_or(Errno::EINVAL)?;
+        // SAFETY: the seals keep the contents from changing and the pages
+        // from going away for as long as the mapping exists.
+        unsafe {
+            MmapRegion::file(&self.fd, 0, len, prot, MapFlags::MAP_PRIVATE)
+        }
+    }
+}
+
+impl FromRawFd for MemFd {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        MemFd {
+            fd: OwnedFd::from_raw_fd(fd),
+        }
+    }
+}
+
+impl AsFd for MemFd {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl AsRawFd for MemFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl From<OwnedFd> for MemFd {
+    fn from(fd: OwnedFd) -> Self {
+        MemFd { fd }
+    }
+}
+
+impl From<MemFd> for OwnedFd {
+    fn from(memfd: MemFd) -> Self {
+        memfd.fd
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_env = "gnu", target_env = "musl"),
+    any(target_arch = "aarch64", target_arch = "x86_64")
+))]
+libc_bitflags!(
+    /// Options that change the behavior of [`memfd_secret`].
+    #[cfg_attr(docsrs, doc(cfg(all())))]
+    pub struct MemFdSecretFlag: libc::c_uint {
+        /// Set the close-on-exec flag on the new file descriptor.
+        O_CLOEXEC as libc::c_uint;
+    }
+);
+
+/// Creates an anonymous file of secret memory, which is only mapped into
+/// the page tables of the processes that map the file, and so not even
+/// accessible to the kernel.
+///
+/// Set its size with [`MemFd::set_len`], then map it with `MAP_SHARED`.  It
+/// cannot be sealed, read or written with `read(2)` and `write(2)`.  Returns
+/// `ENOSYS` if secret memory is disabled, as it is by default before
+/// Linux 6.5.
+///
+/// # Example
+///
+/// ```no_run
+/// # use nix::sys::memfd::{memfd_secret, MemFdSecretFlag};
+/// # use nix::sys::mman::{MapFlags, MmapRegion, ProtFlags};
+/// # use std::num::NonZeroUsize;
+/// let secret = memfd_secret(MemFdSecretFlag::O_CLOEXEC).unwrap();
+/// secret.set_len(4096).unwrap();
+/// // SAFETY: the file is closed right away, so nothing else can resize it.
+/// let mut region = unsafe {
+///     MmapRegion::file(
+///         &secret,
+///         0,
+///         NonZeroUsize::new(4096).unwrap(),
+///         ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+///         MapFlags::MAP_SHARED,
+///     )
+/// }
+/// .unwrap();
+/// drop(secret);
+/// region.as_mut_slice().unwrap()[..6].copy_from_slice(b"hunter");
+/// ```
+///
+/// For more information, see [`memfd_secret(2)`].
+///
+/// [`memfd_secret(2)`]: https://man7.org/linux/man-pages/man2/memfd_secret.2.html
+#[cfg(all(
+    target_os = "linux",
+    any(target_env = "gnu", target_env = "musl"),
+    any(target_arch = "aarch64", target_arch = "x86_64")
+))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+pub fn memfd_secret(flags: MemFdSecretFlag) -> Result<MemFd> {
+    let res = unsafe { libc::syscall(libc::SYS_memfd_secret, flags.bits()) };
+
+    Errno::result(res).map(|r| unsafe { MemFd::from_raw_fd(r as RawFd) })
+}
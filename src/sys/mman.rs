@@ -8,7 +8,16 @@ use crate::Result;
 #[cfg(feature = "fs")]
 use crate::{fcntl::OFlag, sys::stat::Mode};
 use libc::{self, c_int, c_void, off_t, size_t};
-use std::{num::NonZeroUsize, os::unix::io::{AsRawFd, AsFd}};
+#[cfg(all(
+    target_os = "linux",
+    any(target_env = "gnu", target_env = "musl")
+))]
+use std::io::IoSlice;
+use std::{
+    num::NonZeroUsize,
+    os::unix::io::{AsFd, AsRawFd, BorrowedFd},
+    ptr::NonNull,
+};
 
 libc_bitflags! {
     /// Desired memory protection of a memory mapping.
@@ -362,6 +371,33 @@ libc_bitflags! {
     }
 }
 
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+libc_bitflags! {
+    /// Flags for [`mlock2`].
+    pub struct MlockFlags: libc::c_uint {
+        /// Lock pages that are currently resident, and lock the others once
+        /// they are faulted in, rather than faulting all of them in at once.
+        MLOCK_ONFAULT;
+    }
+}
+
+/// Advice for [`process_madvise`].
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(i32)]
+#[non_exhaustive]
+pub enum ProcessAdvise {
+    /// Expect access in the near future.
+    MADV_WILLNEED = libc::MADV_WILLNEED,
+    /// Deactivate the pages, so that they are reclaimed first when memory
+    /// runs low.  Their contents are kept.
+    MADV_COLD = 20,
+    /// Reclaim the pages right away, writing them out to swap or to their
+    /// backing files.  Their contents are kept.
+    MADV_PAGEOUT = 21,
+}
+
 /// Locks all memory pages that contain part of the address range with `length`
 /// bytes starting at `addr`.
 ///
@@ -389,6 +425,25 @@ pub unsafe fn munlock(addr: *const c_void, length: size_t) -> Result<()> {
     Errno::result(libc::munlock(addr, length)).map(drop)
 }
 
+/// Like [`mlock`], with `flags` to control how the pages are locked.
+///
+/// With no flags, this is the same as [`mlock`].  Requires Linux 4.4.
+///
+/// # Safety
+///
+/// `addr` must meet all the requirements described in the [`mlock2(2)`] man
+/// page.
+///
+/// [`mlock2(2)`]: https://man7.org/linux/man-pages/man2/mlock2.2.html
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+pub unsafe fn mlock2(
+    addr: *const c_void,
+    length: size_t,
+    flags: MlockFlags,
+) -> Result<()> {
+    Errno::result(libc::mlock2(addr, length, flags.bits())).map(drop)
+}
+
 /// Locks all memory pages mapped into this process' address space.
 ///
 /// Locked pages never move to the swap area. For more information, see [`mlockall(2)`].
@@ -556,6 +611,288 @@ pub unsafe fn msync(
     Errno::result(libc::msync(addr, length, flags.bits())).map(drop)
 }
 
+/// Find out which pages of the address range with `length` bytes starting at
+/// `addr` are resident in memory, and would not cause a page fault when
+/// accessed.
+///
+/// Returns one entry per page.  `addr` must be a multiple of the page size,
+/// and the whole range must be mapped.  Returns `EINVAL` if `length` does not
+/// fit in the address space.
+///
+/// For more information, see [`mincore(2)`].
+///
+/// [`mincore(2)`]: https://man7.org/linux/man-pages/man2/mincore.2.html
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+pub fn mincore(addr: *const c_void, length: size_t) -> Result<Vec<bool>> {
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let pages =
+        length.checked_add(page_size - 1).ok_or(Errno::EINVAL)? / page_size;
+    let mut vec = vec![0u8; pages];
+    // The kernel only looks at the address, and never at the memory.
+    let res = unsafe {
+        libc::mincore(addr as *mut c_void, length, vec.as_mut_ptr().cast())
+    };
+    Errno::result(res)?;
+    // The other bits are reserved.
+    Ok(vec.into_iter().map(|v| v & 1 != 0).collect())
+}
+
+/// Give advice about the use of memory of the process the `pidfd` refers to.
+///
+/// Each slice of `iov` is an address range in the other process, starting at
+/// a page boundary.  As the kernel does not access their contents on behalf
+/// of the caller, slices of the caller's own memory work for describing the
+/// same addresses in, say, a child forked from it.  Returns the number of
+/// bytes advised about.
+///
+/// The caller needs `CAP_SYS_NICE`, and permission to trace the process.
+/// Requires Linux 5.10.  For more information, see [`process_madvise(2)`].
+///
+/// [`process_madvise(2)`]: https://man7.org/linux/man-pages/man2/process_madvise.2.html
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+pub fn process_madvise<Fd: AsFd>(
+    pidfd: Fd,
+    iov: &[IoSlice<'_>],
+    advice: ProcessAdvise,
+) -> Result<usize> {
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_process_madvise,
+            pidfd.as_fd().as_raw_fd(),
+            iov.as_ptr(),
+            iov.len(),
+            advice as c_int,
+            0 as libc::c_uint,
+        )
+    };
+    Errno::result(res).map(|r| r as usize)
+}
+
+/// Release the memory of the process the `pidfd` refers to, which must have
+/// been killed with `SIGKILL` and be exiting, without waiting for it to exit.
+///
+/// Returns `EINVAL` if the process is not exiting.  Requires Linux 5.15.
+/// For more information, see [`process_mrelease(2)`].
+///
+/// [`process_mrelease(2)`]: https://man7.org/linux/man-pages/man2/process_mrelease.2.html
+#[cfg(all(
+    target_os = "linux",
+    any(
+        target_env = "gnu",
+        all(
+            target_env = "musl",
+            not(any(
+                target_arch = "hexagon",
+                target_arch = "riscv32",
+                target_arch = "riscv64"
+            ))
+        )
+    )
+))]
+pub fn process_mrelease<Fd: AsFd>(pidfd: Fd) -> Result<()> {
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_process_mrelease,
+            pidfd.as_fd().as_raw_fd(),
+            0 as libc::c_uint,
+        )
+    };
+    Errno::result(res).map(drop)
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_env = "gnu", target_env = "musl"),
+    any(target_arch = "aarch64", target_arch = "x86", target_arch = "x86_64")
+))]
+bitflags::bitflags! {
+    /// Access rights of the calling thread to the memory covered by a
+    /// [`ProtectionKey`].
+    pub struct PkeyRights: libc::c_uint {
+        /// Disable all data access to the memory.
+        const PKEY_DISABLE_ACCESS = 0x1;
+        /// Disable writes to the memory.
+        const PKEY_DISABLE_WRITE = 0x2;
+    }
+}
+
+/// A memory protection key, freed when dropped.
+///
+/// Memory tagged with a key using [`pkey_mprotect`] can only be accessed as
+/// the key's rights allow.  The rights are kept per thread, and changing them
+/// does not need a system call, which makes keys cheap to toggle around short
+/// accesses.
+///
+/// Pages still tagged with the key when it is dropped keep their tag, and
+/// would be covered by a key allocated later with the same number.  Tag them
+/// with another key, or unmap them, beforehand.
+///
+/// For more information, see [`pkeys(7)`].
+///
+/// [`pkeys(7)`]: https://man7.org/linux/man-pages/man7/pkeys.7.html
+#[cfg(all(
+    target_os = "linux",
+    any(target_env = "gnu", target_env = "musl"),
+    any(target_arch = "aarch64", target_arch = "x86", target_arch = "x86_64")
+))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct ProtectionKey(c_int);
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_env = "gnu", target_env = "musl"),
+    any(target_arch = "aarch64", target_arch = "x86", target_arch = "x86_64")
+))]
+impl ProtectionKey {
+    /// Allocate a key, with the initial `rights` for the calling thread.
+    ///
+    /// Returns `ENOSPC` when all keys are in use, and `EINVAL` when the CPU
+    /// or the kernel does not support protection keys.
+    #[doc(alias("pkey_alloc"))]
+    pub fn alloc(rights: PkeyRights) -> Result<Self> {
+        let res = unsafe {
+            libc::syscall(
+                libc::SYS_pkey_alloc,
+                0 as libc::c_uint,
+                rights.bits(),
+            )
+        };
+        Errno::result(res).map(|key| ProtectionKey(key as c_int))
+    }
+
+    /// Returns the number of the key.
+    pub fn as_raw(&self) -> c_int {
+        self.0
+    }
+
+    /// Returns the rights of the calling thread to the memory covered by the
+    /// key.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub fn rights(&self) -> PkeyRights {
+        // The key could be allocated, so the CPU supports protection keys.
+        let pkru = unsafe { read_pkru() };
+        PkeyRights::from_bits_truncate((pkru >> (2 * self.0)) & 0b11)
+    }
+
+    /// Change the rights of the calling thread to the memory covered by the
+    /// key.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub fn set_rights(&self, rights: PkeyRights) {
+        let shift = 2 * self.0;
+        // Only the rights of this key change, which is never key `0`.
+        unsafe {
+            let pkru =
+                (read_pkru() & !(0b11 << shift)) | (rights.bits() << shift);
+            write_pkru(pkru);
+        }
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_env = "gnu", target_env = "musl"),
+    any(target_arch = "aarch64", target_arch = "x86", target_arch = "x86_64")
+))]
+impl Drop for ProtectionKey {
+    fn drop(&mut self) {
+        let res = unsafe { libc::syscall(libc::SYS_pkey_free, self.0) };
+        debug_assert!(res == 0, "pkey_free of a protection key failed");
+    }
+}
+
+/// Like [`mprotect`], and additionally tag the memory with `key`.
+///
+/// # Safety
+///
+/// See the [`pkey_mprotect(2)`] man page.  The memory must only be accessed
+/// while the rights of the accessing thread to `key` allow it.
+///
+/// [`pkey_mprotect(2)`]: https://man7.org/linux/man-pages/man2/pkey_mprotect.2.html
+#[cfg(all(
+    target_os = "linux",
+    any(target_env = "gnu", target_env = "musl"),
+    any(target_arch = "aarch64", target_arch = "x86", target_arch = "x86_64")
+))]
+pub unsafe fn pkey_mprotect(
+    addr: *mut c_void,
+    length: size_t,
+    prot: ProtFlags,
+    key: &ProtectionKey,
+) -> Result<()> {
+    let res = libc::syscall(
+        libc::SYS_pkey_mprotect,
+        addr,
+        length,
+        prot.bits(),
+        key.as_raw(),
+    );
+    Errno::result(res).map(drop)
+}
+
+/// Read the protection key rights register of the calling thread.
+///
+/// Key `n` disables access with bit `2 * n`, and writes with bit `2 * n + 1`.
+///
+/// # Safety
+///
+/// The CPU must support protection keys, and the kernel must have enabled
+/// them, which is the case when [`ProtectionKey::alloc`] succeeds.  The CPU
+/// raises `SIGILL` otherwise.
+#[cfg(all(
+    target_os = "linux",
+    any(target_env = "gnu", target_env = "musl"),
+    any(target_arch = "x86", target_arch = "x86_64")
+))]
+#[doc(alias("rdpkru"))]
+pub unsafe fn read_pkru() -> u32 {
+    let pkru: u32;
+    std::arch::asm!(
+        "rdpkru",
+        in("ecx") 0,
+        out("eax") pkru,
+        out("edx") _,
+        options(nomem, nostack, preserves_flags),
+    );
+    pkru
+}
+
+/// Write the protection key rights register of the calling thread.
+///
+/// See [`read_pkru`] for its layout.
+///
+/// # Safety
+///
+/// Protection keys must be supported, as for [`read_pkru`].  Memory whose
+/// access is disabled must not be accessed until it is enabled again.  In
+/// particular, disabling access for key `0`, which covers all memory not
+/// tagged otherwise, makes the thread crash on its next memory access.
+#[cfg(all(
+    target_os = "linux",
+    any(target_env = "gnu", target_env = "musl"),
+    any(target_arch = "x86", target_arch = "x86_64")
+))]
+#[doc(alias("wrpkru"))]
+pub unsafe fn write_pkru(pkru: u32) {
+    // Not `nomem`, so that memory accesses are not moved across it.
+    std::arch::asm!(
+        "wrpkru",
+        in("eax") pkru,
+        in("ecx") 0,
+        in("edx") 0,
+        options(nostack, preserves_flags),
+    );
+}
+
 #[cfg(not(target_os = "android"))]
 feature! {
 #![feature = "fs"]
@@ -603,3 +940,288 @@ pub fn shm_unlink<P: ?Sized + NixPath>(name: &P) -> Result<()> {
 
     Errno::result(ret).map(drop)
 }
+
+/// An owned memory mapping, unmapped when dropped.
+///
+/// Unlike the functions of this module, its methods only ever touch the
+/// memory it owns, and are safe.
+///
+/// # Example
+///
+/// ```
+/// # use nix::sys::mman::{MapFlags, MmapRegion, ProtFlags};
+/// # use std::num::NonZeroUsize;
+/// let mut region = MmapRegion::anonymous(
+///     NonZeroUsize::new(4096).unwrap(),
+///     ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+///     MapFlags::MAP_PRIVATE,
+/// )
+/// .unwrap();
+/// region.as_mut_slice().unwrap()[0] = 0xFF;
+/// region.protect(ProtFlags::PROT_READ).unwrap();
+/// assert!(region.as_mut_slice().is_none());
+/// assert_eq!(region.as_slice().unwrap()[0], 0xFF);
+/// ```
+#[derive(Debug)]
+pub struct MmapRegion {
+    ptr: NonNull<c_void>,
+    len: usize,
+    prot: ProtFlags,
+}
+
+// The mapping is owned memory, just like a `Box<[u8]>`.
+unsafe impl Send for MmapRegion {}
+unsafe impl Sync for MmapRegion {}
+
+impl MmapRegion {
+    /// Map `length` bytes of anonymous memory, which are zero-initialized.
+    ///
+    /// `flags` should contain `MAP_PRIVATE`; `MAP_ANONYMOUS` is added.
+    /// Returns `EINVAL` if `flags` contains `MAP_FIXED`, which could replace
+    /// memory owned elsewhere, or `MAP_SHARED`, which would keep the memory
+    /// shared with child processes after `fork`.  Use [`mmap`] for those.
+    pub fn anonymous(
+        length: NonZeroUsize,
+        prot: ProtFlags,
+        flags: MapFlags,
+    ) -> Result<Self> {
+        if flags.intersects(MapFlags::MAP_SHARED) {
+            return Err(Errno::EINVAL);
+        }
+        Self::new::<BorrowedFd>(
+            None,
+            length,
+            prot,
+            flags | MapFlags::MAP_ANONYMOUS,
+            None,
+            0,
+        )
+    }
+
+    /// Map `length` bytes of the file `fd` refers to, starting at `offset`,
+    /// which must be a multiple of the page size.
+    ///
+    /// `flags` should contain either `MAP_PRIVATE` or `MAP_SHARED`.  Returns
+    /// `EINVAL` if `flags` contains `MAP_FIXED`, which could replace memory
+    /// owned elsewhere.
+    ///
+    /// # Safety
+    ///
+    /// The slices returned by [`as_slice`](Self::as_slice) and
+    /// [`as_mut_slice`](Self::as_mut_slice) assume that nothing else changes
+    /// the memory while they are borrowed.  So, as long as the mapping is
+    /// accessed through them, the file must not be modified by other means,
+    /// or other mappings.  Besides, accessing pages past the end of the file
+    /// raises `SIGBUS`, so the file must not be truncated either.
+    pub unsafe fn file<F: AsFd>(
+        fd: F,
+        offset: off_t,
+        length: NonZeroUsize,
+        prot: ProtFlags,
+        flags: MapFlags,
+    ) -> Result<Self> {
+        Self::new(None, length, prot, flags, Some(fd), offset)
+    }
+
+    /// Map `length` bytes of anonymous memory at exactly `addr`, which must
+    /// be a multiple of the page size.
+    ///
+    /// `MAP_ANONYMOUS` and `MAP_FIXED_NOREPLACE` are added to `flags`.
+    /// Returns `EEXIST` if the range overlaps an existing mapping, and
+    /// `EINVAL` if `flags` contains `MAP_SHARED`, as for
+    /// [`anonymous`](Self::anonymous).
+    #[cfg(target_os = "linux")]
+    #[cfg_attr(docsrs, doc(cfg(all())))]
+    pub fn fixed_noreplace(
+        addr: NonZeroUsize,
+        length: NonZeroUsize,
+        prot: ProtFlags,
+        flags: MapFlags,
+    ) -> Result<Self> {
+        if flags.intersects(MapFlags::MAP_SHARED) {
+            return Err(Errno::EINVAL);
+        }
+        let flags =
+            flags | MapFlags::MAP_ANONYMOUS | MapFlags::MAP_FIXED_NOREPLACE;
+        let region =
+            Self::new::<BorrowedFd>(Some(addr), length, prot, flags, None, 0)?;
+        // Before Linux 4.17 the flag is unknown, and the address only a hint.
+        if region.as_ptr() as usize != usize::from(addr) {
+            return Err(Errno::EEXIST);
+        }
+        Ok(region)
+    }
+
+    fn new<F: AsFd>(
+        addr: Option<NonZeroUsize>,
+        length: NonZeroUsize,
+        prot: ProtFlags,
+        flags: MapFlags,
+        f: Option<F>,
+        offset: off_t,
+    ) -> Result<Self> {
+        if flags.contains(MapFlags::MAP_FIXED) {
+            return Err(Errno::EINVAL);
+        }
+        let ptr = unsafe { mmap(addr, length, prot, flags, f, offset) }?;
+        Ok(MmapRegion {
+            ptr: NonNull::new(ptr).unwrap(),
+            len: length.into(),
+            prot,
+        })
+    }
+
+    /// Returns a pointer to the start of the mapping.
+    pub fn as_ptr(&self) -> *mut c_void {
+        self.ptr.as_ptr()
+    }
+
+    /// Returns the length of the mapping, in bytes.
+    pub fn size(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the current protection of the mapping.
+    pub fn prot(&self) -> ProtFlags {
+        self.prot
+    }
+
+    /// Returns the contents of the mapping, or `None` if its protection does
+    /// not contain `PROT_READ`.
+    pub fn as_slice(&self) -> Option<&[u8]> {
+        if !self.prot.contains(ProtFlags::PROT_READ) {
+            return None;
+        }
+        Some(unsafe {
+            std::slice::from_raw_parts(self.ptr.as_ptr().cast(), self.len)
+        })
+    }
+
+    /// Returns the contents of the mapping, or `None` if its protection does
+    /// not contain both `PROT_READ` and `PROT_WRITE`.
+    pub fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        if !self
+            .prot
+            .contains(ProtFlags::PROT_READ | ProtFlags::PROT_WRITE)
+        {
+            return None;
+        }
+        Some(unsafe {
+            std::slice::from_raw_parts_mut(self.ptr.as_ptr().cast(), self.len)
+        })
+    }
+
+    /// Change the protection of the mapping.  See [`mprotect`].
+    pub fn protect(&mut self, prot: ProtFlags) -> Result<()> {
+        unsafe { mprotect(self.as_ptr(), self.len, prot) }?;
+        self.prot = prot;
+        Ok(())
+    }
+
+    /// Give advice about the use of the mapping.  See [`madvise`].
+    ///
+    /// Only advice that leaves the contents of the mapping intact, like
+    /// `MADV_WILLNEED`, is accepted.  Returns `EINVAL` for other advice,
+    /// which needs [`advise_unchecked`](Self::advise_unchecked).
+    pub fn advise(&self, advise: MmapAdvise) -> Result<()> {
+        match advise {
+            MmapAdvise::MADV_NORMAL
+            | MmapAdvise::MADV_RANDOM
+            | MmapAdvise::MADV_SEQUENTIAL
+            | MmapAdvise::MADV_WILLNEED => (),
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            MmapAdvise::MADV_DOFORK
+            | MmapAdvise::MADV_MERGEABLE
+            | MmapAdvise::MADV_UNMERGEABLE
+            | MmapAdvise::MADV_HUGEPAGE
+            | MmapAdvise::MADV_NOHUGEPAGE
+            | MmapAdvise::MADV_DONTDUMP
+            | MmapAdvise::MADV_DODUMP => (),
+            #[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
+            MmapAdvise::MADV_NOSYNC
+            | MmapAdvise::MADV_AUTOSYNC
+            | MmapAdvise::MADV_NOCORE
+            | MmapAdvise::MADV_CORE => (),
+            _ => return Err(Errno::EINVAL),
+        }
+        unsafe { madvise(self.as_ptr(), self.len, advise) }
+    }
+
+    /// Give any advice about the use of the mapping.  See [`madvise`].
+    ///
+    /// # Safety
+    ///
+    /// Some advice changes the contents of the mapping, like `MADV_DONTNEED`
+    /// or `MADV_FREE`, or leaves it out of child processes, like
+    /// `MADV_DONTFORK`.  The caller must make sure that no code relies on
+    /// what the advice takes away, including the copies of this
+    /// `MmapRegion` in child processes.
+    pub unsafe fn advise_unchecked(
+        &mut self,
+        advise: MmapAdvise,
+    ) -> Result<()> {
+        madvise(self.as_ptr(), self.len, advise)
+    }
+
+    /// Write changes made to a file-backed mapping back to the file.
+    /// See [`msync`].
+    pub fn sync(&self, flags: MsFlags) -> Result<()> {
+        unsafe { msync(self.as_ptr(), self.len, flags) }
+    }
+
+    /// Lock the pages of the mapping in memory.  See [`mlock`].
+    pub fn lock(&self) -> Result<()> {
+        unsafe { mlock(self.as_ptr(), self.len) }
+    }
+
+    /// Unlock the pages of the mapping.  See [`munlock`].
+    pub fn unlock(&self) -> Result<()> {
+        unsafe { munlock(self.as_ptr(), self.len) }
+    }
+
+    /// Grow or shrink the mapping to `new_length` bytes.  See [`mremap`].
+    ///
+    /// Unless `flags` allows it to move, the mapping can only grow if the
+    /// pages following it are free.
+    #[cfg(any(target_os = "linux", target_os = "netbsd"))]
+    #[cfg_attr(docsrs, doc(cfg(all())))]
+    pub fn remap(
+        &mut self,
+        new_length: NonZeroUsize,
+        flags: MRemapFlags,
+    ) -> Result<()> {
+        let ptr = unsafe {
+            mremap(self.as_ptr(), self.len, new_length.into(), flags, None)
+        }?;
+        self.ptr = NonNull::new(ptr).unwrap();
+        self.len = new_length.into();
+        Ok(())
+    }
+
+    /// Split the mapping in two at `offset` bytes from its start, which must
+    /// be a multiple of the page size, keeping the head and returning the
+    /// tail.
+    ///
+    /// Returns `EINVAL` if `offset` is not strictly within the mapping.
+    pub fn split(&mut self, offset: usize) -> Result<Self> {
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        if offset == 0 || offset >= self.len || offset % page_size != 0 {
+            return Err(Errno::EINVAL);
+        }
+        let tail = unsafe { self.ptr.as_ptr().cast::<u8>().add(offset) };
+        let tail = MmapRegion {
+            ptr: NonNull::new(tail.cast()).unwrap(),
+            len: self.len - offset,
+            prot: self.prot,
+        };
+        self.len = offset;
+        Ok(tail)
+    }
+}
+
+impl Drop for MmapRegion {
+    fn drop(&mut self) {
+        let res = unsafe { munmap(self.as_ptr(), self.len) };
+        debug_assert!(res.is_ok(), "munmap of a memory mapping failed");
+    }
+}
@@ -28,7 +28,14 @@ feature! {
     #[allow(missing_docs)]
     pub mod event;
 
-    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos",
+              target_os = "netbsd",
+              target_os = "openbsd"))]
     #[allow(missing_docs)]
     pub mod eventfd;
 }
@@ -139,6 +146,11 @@ feature! {
     pub mod signalfd;
 }
 
+feature! {
+    #![feature = "signal"]
+    pub mod signalpipe;
+}
+
 #[cfg(not(target_os = "redox"))]
 feature! {
     #![feature = "socket"]
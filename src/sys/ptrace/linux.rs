@@ -1,6 +1,8 @@
 //! For detailed description of the ptrace requests, consult `man ptrace`.
 
 use crate::errno::Errno;
+#[cfg(all(target_os = "linux", feature = "signal"))]
+use crate::sys::signal::SigSet;
 use crate::sys::signal::Signal;
 use crate::unistd::Pid;
 use crate::Result;
@@ -121,6 +123,9 @@ libc_enum! {
         #[cfg(all(target_os = "linux", target_env = "gnu",
                   any(target_arch = "x86", target_arch = "x86_64")))]
         PTRACE_SYSEMU_SINGLESTEP,
+        #[cfg(all(target_os = "linux", target_env = "gnu"))]
+        #[cfg_attr(docsrs, doc(cfg(all())))]
+        PTRACE_GET_SYSCALL_INFO,
     }
 }
 
@@ -309,6 +314,295 @@ pub fn setsiginfo(pid: Pid, sig: &siginfo_t) -> Result<()> {
     }
 }
 
+/// Details of the system call a tracee is stopped in, as returned by
+/// [`get_syscall_info`].
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum SyscallInfo {
+    /// The tracee is not stopped in a system call.
+    None,
+    /// The tracee is stopped on entry to a system call.
+    Entry {
+        /// The system call number.
+        nr: u64,
+        /// The system call arguments.
+        args: [u64; 6],
+        /// The `AUDIT_ARCH_*` value of the system call convention in use.
+        arch: u32,
+        /// The instruction pointer.
+        ip: u64,
+        /// The stack pointer.
+        sp: u64,
+    },
+    /// The tracee is stopped on exit from a system call.
+    Exit {
+        /// The return value of the system call, or the negated error number
+        /// if `is_error` is set.
+        rval: i64,
+        /// Whether the system call failed.
+        is_error: bool,
+    },
+    /// The tracee is stopped by a `SECCOMP_RET_TRACE` filter rule.
+    Seccomp {
+        /// The system call number.
+        nr: u64,
+        /// The system call arguments.
+        args: [u64; 6],
+        /// The `SECCOMP_RET_DATA` part of the filter's return value.
+        ret_data: u32,
+    },
+}
+
+/// Get details of the system call the tracee is stopped in, as with
+/// `ptrace(PTRACE_GET_SYSCALL_INFO, ...)`.
+///
+/// Unlike [`getregs`], this works the same way on every architecture.  The
+/// tracee must be in a syscall-stop, or a `PTRACE_EVENT_SECCOMP` stop, for
+/// anything other than [`SyscallInfo::None`] to be returned.  Requires Linux
+/// 5.3 or later.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+pub fn get_syscall_info(pid: Pid) -> Result<SyscallInfo> {
+    let mut info = mem::MaybeUninit::<libc::ptrace_syscall_info>::zeroed();
+    let res = unsafe {
+        libc::ptrace(
+            Request::PTRACE_GET_SYSCALL_INFO as RequestType,
+            libc::pid_t::from(pid),
+            mem::size_of::<libc::ptrace_syscall_info>(),
+            info.as_mut_ptr(),
+        )
+    };
+    Errno::result(res)?;
+    // The kernel fills in as much of the struct as it has data for, and the
+    // rest was zeroed.
+    let info = unsafe { info.assume_init() };
+    Ok(unsafe {
+        match info.op {
+            libc::PTRACE_SYSCALL_INFO_ENTRY => SyscallInfo::Entry {
+                nr: info.u.entry.nr,
+                args: info.u.entry.args,
+                arch: info.arch,
+                ip: info.instruction_pointer,
+                sp: info.stack_pointer,
+            },
+            libc::PTRACE_SYSCALL_INFO_EXIT => SyscallInfo::Exit {
+                rval: info.u.exit.sval,
+                is_error: info.u.exit.is_error != 0,
+            },
+            libc::PTRACE_SYSCALL_INFO_SECCOMP => SyscallInfo::Seccomp {
+                nr: info.u.seccomp.nr,
+                args: info.u.seccomp.args,
+                ret_data: info.u.seccomp.ret_data,
+            },
+            _ => SyscallInfo::None,
+        }
+    })
+}
+
+// Requests that libc does not define on every target.  Their values are the
+// same on every architecture.
+#[cfg(target_os = "linux")]
+const PTRACE_GETSIGMASK: RequestType = 0x420a;
+#[cfg(target_os = "linux")]
+const PTRACE_SETSIGMASK: RequestType = 0x420b;
+#[cfg(target_os = "linux")]
+const PTRACE_SECCOMP_GET_FILTER: RequestType = 0x420c;
+#[cfg(target_os = "linux")]
+const PTRACE_GET_RSEQ_CONFIGURATION: RequestType = 0x420f;
+
+/// The size of the kernel's `sigset_t`, which is smaller than libc's.
+#[cfg(all(
+    target_os = "linux",
+    feature = "signal",
+    any(target_arch = "mips", target_arch = "mips64")
+))]
+const KERNEL_SIGSET_SIZE: usize = 16;
+#[cfg(all(
+    target_os = "linux",
+    feature = "signal",
+    not(any(target_arch = "mips", target_arch = "mips64"))
+))]
+const KERNEL_SIGSET_SIZE: usize = 8;
+
+/// Read signals pending for the tracee without removing them, as with
+/// `ptrace(PTRACE_PEEKSIGINFO, ...)`.
+///
+/// `range` selects signals by their position in the queue.  If `shared` is
+/// set, the queue of signals pending for the whole process is read instead
+/// of the thread's own one.  Fewer signals than requested are returned if the
+/// queue is shorter.
+#[cfg(all(
+    target_os = "linux",
+    not(any(target_arch = "mips", target_arch = "mips64"))
+))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+pub fn peek_siginfo(
+    pid: Pid,
+    shared: bool,
+    range: std::ops::Range<usize>,
+) -> Result<Vec<siginfo_t>> {
+    // Not defined by libc for every target
+    #[repr(C)]
+    struct PeekSigInfoArgs {
+        off: u64,
+        flags: u32,
+        nr: i32,
+    }
+
+    // Read the queue in chunks, so that a huge `range` does not make us
+    // allocate room for signals that are not there.
+    const CHUNK: usize = 64;
+
+    let nr = range.end.saturating_sub(range.start);
+    let mut buf = Vec::<siginfo_t>::new();
+    while buf.len() < nr {
+        let want = (nr - buf.len()).min(CHUNK);
+        let args = PeekSigInfoArgs {
+            off: (range.start + buf.len()) as u64,
+            // PTRACE_PEEKSIGINFO_SHARED
+            flags: u32::from(shared),
+            nr: want as i32,
+        };
+        buf.reserve(want);
+        let res = unsafe {
+            libc::ptrace(
+                Request::PTRACE_PEEKSIGINFO as RequestType,
+                libc::pid_t::from(pid),
+                &args as *const PeekSigInfoArgs as *mut c_void,
+                buf.as_mut_ptr().add(buf.len()) as *mut c_void,
+            )
+        };
+        let n = Errno::result(res)? as usize;
+        // The kernel wrote `n` entries after the ones we already had
+        unsafe { buf.set_len(buf.len() + n) };
+        if n < want {
+            break;
+        }
+    }
+    Ok(buf)
+}
+
+/// Get the signal mask of the tracee, as with
+/// `ptrace(PTRACE_GETSIGMASK, ...)`.
+#[cfg(all(target_os = "linux", feature = "signal"))]
+#[cfg_attr(docsrs, doc(cfg(all(target_os = "linux", feature = "signal"))))]
+pub fn get_sigmask(pid: Pid) -> Result<SigSet> {
+    let mut mask = SigSet::empty();
+    let res = unsafe {
+        libc::ptrace(
+            PTRACE_GETSIGMASK,
+            libc::pid_t::from(pid),
+            KERNEL_SIGSET_SIZE as *mut c_void,
+            &mut mask as *mut SigSet as *mut c_void,
+        )
+    };
+    Errno::result(res).map(|_| mask)
+}
+
+/// Set the signal mask of the tracee, as with
+/// `ptrace(PTRACE_SETSIGMASK, ...)`.
+///
+/// `SIGKILL` and `SIGSTOP` are never blocked.
+#[cfg(all(target_os = "linux", feature = "signal"))]
+#[cfg_attr(docsrs, doc(cfg(all(target_os = "linux", feature = "signal"))))]
+pub fn set_sigmask(pid: Pid, mask: &SigSet) -> Result<()> {
+    let res = unsafe {
+        libc::ptrace(
+            PTRACE_SETSIGMASK,
+            libc::pid_t::from(pid),
+            KERNEL_SIGSET_SIZE as *mut c_void,
+            mask as *const SigSet as *mut c_void,
+        )
+    };
+    Errno::result(res).map(drop)
+}
+
+/// Get a classic BPF seccomp filter installed in the tracee, as with
+/// `ptrace(PTRACE_SECCOMP_GET_FILTER, ...)`.
+///
+/// `index` counts back from the most recently installed filter, starting at
+/// zero.  Returns `ENOENT` past the last filter, and `EMEDIUMTYPE` for
+/// filters that are not classic BPF.  Requires `CAP_SYS_ADMIN`.
+#[cfg(target_os = "linux")]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+pub fn get_seccomp_filter(
+    pid: Pid,
+    index: usize,
+) -> Result<Vec<libc::sock_filter>> {
+    let get = |data: *mut libc::sock_filter| {
+        Errno::result(unsafe {
+            libc::ptrace(
+                PTRACE_SECCOMP_GET_FILTER,
+                libc::pid_t::from(pid),
+                index as *mut c_void,
+                data as *mut c_void,
+            )
+        })
+        .map(|len| len as usize)
+    };
+    // The first call only returns the number of instructions.
+    let len = get(ptr::null_mut())?;
+    let mut filter = Vec::<libc::sock_filter>::with_capacity(len);
+    let len = get(filter.as_mut_ptr())?.min(len);
+    // The kernel wrote `len` instructions
+    unsafe { filter.set_len(len) };
+    Ok(filter)
+}
+
+/// The restartable sequences registration of a tracee, as returned by
+/// [`get_rseq_configuration`].
+#[cfg(target_os = "linux")]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct RseqConfiguration {
+    /// The address of the registered `struct rseq`, or zero if none is.
+    pub rseq_abi_pointer: u64,
+    /// The size of the registered `struct rseq`.
+    pub rseq_abi_size: u32,
+    /// The signature that has to precede abort handlers.
+    pub signature: u32,
+    /// The flags the `struct rseq` was registered with.
+    pub flags: u32,
+}
+
+/// Get the restartable sequences registration of the tracee, as with
+/// `ptrace(PTRACE_GET_RSEQ_CONFIGURATION, ...)`.
+///
+/// Requires Linux 5.13 or later.
+#[cfg(target_os = "linux")]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+pub fn get_rseq_configuration(pid: Pid) -> Result<RseqConfiguration> {
+    // struct ptrace_rseq_configuration
+    #[repr(C)]
+    #[derive(Default)]
+    struct Raw {
+        rseq_abi_pointer: u64,
+        rseq_abi_size: u32,
+        signature: u32,
+        flags: u32,
+        pad: u32,
+    }
+
+    let mut raw = Raw::default();
+    let res = unsafe {
+        libc::ptrace(
+            PTRACE_GET_RSEQ_CONFIGURATION,
+            libc::pid_t::from(pid),
+            mem::size_of::<Raw>() as *mut c_void,
+            &mut raw as *mut Raw as *mut c_void,
+        )
+    };
+    Errno::result(res)?;
+    Ok(RseqConfiguration {
+        rseq_abi_pointer: raw.rseq_abi_pointer,
+        rseq_abi_size: raw.rseq_abi_size,
+        signature: raw.signature,
+        flags: raw.flags,
+    })
+}
+
 /// Sets the process as traceable, as with `ptrace(PTRACE_TRACEME, ...)`
 ///
 /// Indicates that this process is to be traced by its parent.
@@ -424,6 +718,28 @@ pub fn cont<T: Into<Option<Signal>>>(pid: Pid, sig: T) -> Result<()> {
     }
 }
 
+/// Let a tracee in a group-stop wait for `SIGCONT` without running, as with
+/// `ptrace(PTRACE_LISTEN, ...)`
+///
+/// Only valid for tracees attached with [`seize`].  The tracer is notified
+/// again when the group-stop ends.
+#[cfg(all(
+    target_os = "linux",
+    not(any(target_arch = "mips", target_arch = "mips64"))
+))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+pub fn listen(pid: Pid) -> Result<()> {
+    unsafe {
+        ptrace_other(
+            Request::PTRACE_LISTEN,
+            pid,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        )
+        .map(drop)
+    }
+}
+
 /// Stop a tracee, as with `ptrace(PTRACE_INTERRUPT, ...)`
 ///
 /// This request is equivalent to `ptrace(PTRACE_INTERRUPT, ...)`
@@ -556,3 +872,468 @@ pub unsafe fn write_user(
 ) -> Result<()> {
     ptrace_other(Request::PTRACE_POKEUSER, pid, offset, data).map(drop)
 }
+
+/// The type of a register set, as passed to `PTRACE_GETREGSET` and
+/// `PTRACE_SETREGSET`.  These are the ELF core dump note types.
+#[cfg(all(
+    target_os = "linux",
+    target_env = "gnu",
+    not(any(target_arch = "mips", target_arch = "mips64"))
+))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(i32)]
+#[non_exhaustive]
+pub enum NoteType {
+    /// General purpose registers.
+    NT_PRSTATUS = libc::NT_PRSTATUS,
+    /// Floating point registers.
+    NT_PRFPREG = libc::NT_PRFPREG,
+    /// The extended processor state saved by `XSAVE`.  Its size depends on
+    /// the features the CPU supports.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    NT_X86_XSTATE = 0x202,
+    /// The TLS register, `tpidr_el0`.
+    #[cfg(target_arch = "aarch64")]
+    NT_ARM_TLS = 0x401,
+    /// Hardware breakpoint registers.
+    #[cfg(target_arch = "aarch64")]
+    NT_ARM_HW_BREAK = 0x402,
+    /// Hardware watchpoint registers.
+    #[cfg(target_arch = "aarch64")]
+    NT_ARM_HW_WATCH = 0x403,
+    /// The system call number.
+    #[cfg(target_arch = "aarch64")]
+    NT_ARM_SYSTEM_CALL = 0x404,
+    /// Scalable Vector Extension registers.  Their size depends on the vector
+    /// length.
+    #[cfg(target_arch = "aarch64")]
+    NT_ARM_SVE = 0x405,
+    /// Pointer authentication code masks.
+    #[cfg(target_arch = "aarch64")]
+    NT_ARM_PAC_MASK = 0x406,
+}
+
+/// A register set that can be read with [`getregset`] and written with
+/// [`setregset`].  See the [`regset`] module for the implementations.
+///
+/// # Safety
+///
+/// `Regs` must match the layout the kernel uses for the `VALUE` note type,
+/// and any bit pattern, including all zeros, must be a valid `Regs`.
+#[cfg(all(
+    target_os = "linux",
+    target_env = "gnu",
+    not(any(target_arch = "mips", target_arch = "mips64"))
+))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+pub unsafe trait RegSet {
+    /// The note type identifying the register set.
+    const VALUE: NoteType;
+    /// The structure the register set is transferred as.
+    type Regs;
+}
+
+/// Register sets with a fixed layout, for use with [`getregset`] and
+/// [`setregset`].
+#[cfg(all(
+    target_os = "linux",
+    target_env = "gnu",
+    not(any(target_arch = "mips", target_arch = "mips64"))
+))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+#[allow(non_camel_case_types)]
+pub mod regset {
+    use super::{NoteType, RegSet};
+
+    /// General purpose registers.
+    #[cfg(any(
+        target_arch = "aarch64",
+        target_arch = "riscv64",
+        target_arch = "x86",
+        target_arch = "x86_64"
+    ))]
+    #[derive(Clone, Copy, Debug)]
+    pub enum NT_PRSTATUS {}
+
+    #[cfg(any(
+        target_arch = "aarch64",
+        target_arch = "x86",
+        target_arch = "x86_64"
+    ))]
+    unsafe impl RegSet for NT_PRSTATUS {
+        const VALUE: NoteType = NoteType::NT_PRSTATUS;
+        type Regs = libc::user_regs_struct;
+    }
+
+    #[cfg(target_arch = "riscv64")]
+    unsafe impl RegSet for NT_PRSTATUS {
+        const VALUE: NoteType = NoteType::NT_PRSTATUS;
+        type Regs = super::user_regs_struct;
+    }
+
+    /// Floating point registers.
+    #[cfg(any(
+        target_arch = "aarch64",
+        target_arch = "x86",
+        target_arch = "x86_64"
+    ))]
+    #[derive(Clone, Copy, Debug)]
+    pub enum NT_PRFPREG {}
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    unsafe impl RegSet for NT_PRFPREG {
+        const VALUE: NoteType = NoteType::NT_PRFPREG;
+        type Regs = libc::user_fpregs_struct;
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    unsafe impl RegSet for NT_PRFPREG {
+        const VALUE: NoteType = NoteType::NT_PRFPREG;
+        type Regs = libc::user_fpsimd_struct;
+    }
+
+    /// The TLS register, `tpidr_el0`.
+    #[cfg(target_arch = "aarch64")]
+    #[derive(Clone, Copy, Debug)]
+    pub enum NT_ARM_TLS {}
+
+    #[cfg(target_arch = "aarch64")]
+    unsafe impl RegSet for NT_ARM_TLS {
+        const VALUE: NoteType = NoteType::NT_ARM_TLS;
+        type Regs = u64;
+    }
+
+    /// The system call number.  Writing it changes the system call the
+    /// tracee is about to make.
+    #[cfg(target_arch = "aarch64")]
+    #[derive(Clone, Copy, Debug)]
+    pub enum NT_ARM_SYSTEM_CALL {}
+
+    #[cfg(target_arch = "aarch64")]
+    unsafe impl RegSet for NT_ARM_SYSTEM_CALL {
+        const VALUE: NoteType = NoteType::NT_ARM_SYSTEM_CALL;
+        type Regs = libc::c_int;
+    }
+}
+
+/// General purpose registers, as transferred by `NT_PRSTATUS`.
+///
+/// libc does not define this structure for riscv64.
+#[cfg(all(target_os = "linux", target_env = "gnu", target_arch = "riscv64"))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+#[allow(missing_docs, non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[repr(C)]
+pub struct user_regs_struct {
+    pub pc: u64,
+    pub ra: u64,
+    pub sp: u64,
+    pub gp: u64,
+    pub tp: u64,
+    pub t0: u64,
+    pub t1: u64,
+    pub t2: u64,
+    pub s0: u64,
+    pub s1: u64,
+    pub a0: u64,
+    pub a1: u64,
+    pub a2: u64,
+    pub a3: u64,
+    pub a4: u64,
+    pub a5: u64,
+    pub a6: u64,
+    pub a7: u64,
+    pub s2: u64,
+    pub s3: u64,
+    pub s4: u64,
+    pub s5: u64,
+    pub s6: u64,
+    pub s7: u64,
+    pub s8: u64,
+    pub s9: u64,
+    pub s10: u64,
+    pub s11: u64,
+    pub t3: u64,
+    pub t4: u64,
+    pub t5: u64,
+    pub t6: u64,
+}
+
+/// Get a register set, as with `ptrace(PTRACE_GETREGSET, ...)`.
+///
+/// # Example
+///
+/// ```no_run
+/// # use nix::sys::ptrace::{getregset, regset, Registers};
+/// # use nix::unistd::Pid;
+/// # fn f(pid: Pid) -> nix::Result<()> {
+/// let regs = getregset::<regset::NT_PRSTATUS>(pid)?;
+/// println!("stopped at {:#x}", regs.ip());
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(all(
+    target_os = "linux",
+    target_env = "gnu",
+    not(any(target_arch = "mips", target_arch = "mips64"))
+))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+pub fn getregset<S: RegSet>(pid: Pid) -> Result<S::Regs> {
+    let mut data = mem::MaybeUninit::<S::Regs>::zeroed();
+    let mut iov = libc::iovec {
+        iov_base: data.as_mut_ptr().cast(),
+        iov_len: mem::size_of::<S::Regs>(),
+    };
+    let res = unsafe {
+        libc::ptrace(
+            Request::PTRACE_GETREGSET as RequestType,
+            libc::pid_t::from(pid),
+            S::VALUE as i32 as usize as *mut c_void,
+            &mut iov as *mut libc::iovec as *mut c_void,
+        )
+    };
+    Errno::result(res)?;
+    // The kernel may have filled in less than the whole structure, but the
+    // rest is zeroed and `RegSet` guarantees that is valid.
+    Ok(unsafe { data.assume_init() })
+}
+
+/// Set a register set, as with `ptrace(PTRACE_SETREGSET, ...)`.
+#[cfg(all(
+    target_os = "linux",
+    target_env = "gnu",
+    not(any(target_arch = "mips", target_arch = "mips64"))
+))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+pub fn setregset<S: RegSet>(pid: Pid, regs: &S::Regs) -> Result<()> {
+    let mut iov = libc::iovec {
+        iov_base: regs as *const S::Regs as *mut c_void,
+        iov_len: mem::size_of::<S::Regs>(),
+    };
+    let res = unsafe {
+        libc::ptrace(
+            Request::PTRACE_SETREGSET as RequestType,
+            libc::pid_t::from(pid),
+            S::VALUE as i32 as usize as *mut c_void,
+            &mut iov as *mut libc::iovec as *mut c_void,
+        )
+    };
+    Errno::result(res).map(drop)
+}
+
+/// Read the register set `note` into `buf`, returning the number of bytes
+/// the kernel wrote.
+///
+/// This is for register sets whose size depends on the CPU, such as
+/// `NT_X86_XSTATE` and `NT_ARM_SVE`.  If `buf` is too small the register set
+/// is truncated.
+#[cfg(all(
+    target_os = "linux",
+    target_env = "gnu",
+    not(any(target_arch = "mips", target_arch = "mips64"))
+))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+pub fn getregset_bytes(
+    pid: Pid,
+    note: NoteType,
+    buf: &mut [u8],
+) -> Result<usize> {
+    let mut iov = libc::iovec {
+        iov_base: buf.as_mut_ptr().cast(),
+        iov_len: buf.len(),
+    };
+    let res = unsafe {
+        libc::ptrace(
+            Request::PTRACE_GETREGSET as RequestType,
+            libc::pid_t::from(pid),
+            note as i32 as usize as *mut c_void,
+            &mut iov as *mut libc::iovec as *mut c_void,
+        )
+    };
+    Errno::result(res).map(|_| iov.iov_len)
+}
+
+/// Write the register set `note` from `buf`, as read by
+/// [`getregset_bytes`].
+#[cfg(all(
+    target_os = "linux",
+    target_env = "gnu",
+    not(any(target_arch = "mips", target_arch = "mips64"))
+))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+pub fn setregset_bytes(pid: Pid, note: NoteType, buf: &[u8]) -> Result<()> {
+    let mut iov = libc::iovec {
+        iov_base: buf.as_ptr() as *mut c_void,
+        iov_len: buf.len(),
+    };
+    let res = unsafe {
+        libc::ptrace(
+            Request::PTRACE_SETREGSET as RequestType,
+            libc::pid_t::from(pid),
+            note as i32 as usize as *mut c_void,
+            &mut iov as *mut libc::iovec as *mut c_void,
+        )
+    };
+    Errno::result(res).map(drop)
+}
+
+/// An architecture independent view of the general purpose registers, as
+/// read with `getregset::<regset::NT_PRSTATUS>`.
+///
+/// Values are zero extended to 64 bits on 32 bit architectures.
+#[cfg(all(
+    target_os = "linux",
+    target_env = "gnu",
+    any(
+        target_arch = "aarch64",
+        target_arch = "riscv64",
+        target_arch = "x86",
+        target_arch = "x86_64"
+    )
+))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+pub trait Registers {
+    /// The instruction pointer.
+    fn ip(&self) -> u64;
+    /// Set the instruction pointer.
+    fn set_ip(&mut self, ip: u64);
+    /// The stack pointer.
+    fn sp(&self) -> u64;
+    /// Set the stack pointer.
+    fn set_sp(&mut self, sp: u64);
+    /// The register holding a function or system call return value.
+    fn return_value(&self) -> u64;
+    /// Set the register holding a function or system call return value.
+    fn set_return_value(&mut self, value: u64);
+    /// The number of the system call the tracee is making.  Only meaningful
+    /// at a syscall-stop.
+    fn syscall_number(&self) -> u64;
+    /// The arguments of the system call the tracee is making.  Only
+    /// meaningful at a syscall-stop.
+    ///
+    /// On aarch64 and riscv64 the first argument shares a register with the
+    /// return value, so it is overwritten once the system call returns.
+    fn syscall_args(&self) -> [u64; 6];
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu", target_arch = "x86_64"))]
+impl Registers for libc::user_regs_struct {
+    fn ip(&self) -> u64 {
+        self.rip
+    }
+    fn set_ip(&mut self, ip: u64) {
+        self.rip = ip;
+    }
+    fn sp(&self) -> u64 {
+        self.rsp
+    }
+    fn set_sp(&mut self, sp: u64) {
+        self.rsp = sp;
+    }
+    fn return_value(&self) -> u64 {
+        self.rax
+    }
+    fn set_return_value(&mut self, value: u64) {
+        self.rax = value;
+    }
+    fn syscall_number(&self) -> u64 {
+        self.orig_rax
+    }
+    fn syscall_args(&self) -> [u64; 6] {
+        [self.rdi, self.rsi, self.rdx, self.r10, self.r8, self.r9]
+    }
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu", target_arch = "x86"))]
+impl Registers for libc::user_regs_struct {
+    fn ip(&self) -> u64 {
+        self.eip as u32 as u64
+    }
+    fn set_ip(&mut self, ip: u64) {
+        self.eip = ip as c_long;
+    }
+    fn sp(&self) -> u64 {
+        self.esp as u32 as u64
+    }
+    fn set_sp(&mut self, sp: u64) {
+        self.esp = sp as c_long;
+    }
+    fn return_value(&self) -> u64 {
+        self.eax as u32 as u64
+    }
+    fn set_return_value(&mut self, value: u64) {
+        self.eax = value as c_long;
+    }
+    fn syscall_number(&self) -> u64 {
+        self.orig_eax as u32 as u64
+    }
+    fn syscall_args(&self) -> [u64; 6] {
+        [self.ebx, self.ecx, self.edx, self.esi, self.edi, self.ebp]
+            .map(|r| r as u32 as u64)
+    }
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu", target_arch = "aarch64"))]
+impl Registers for libc::user_regs_struct {
+    fn ip(&self) -> u64 {
+        self.pc
+    }
+    fn set_ip(&mut self, ip: u64) {
+        self.pc = ip;
+    }
+    fn sp(&self) -> u64 {
+        self.sp
+    }
+    fn set_sp(&mut self, sp: u64) {
+        self.sp = sp;
+    }
+    fn return_value(&self) -> u64 {
+        self.regs[0]
+    }
+    fn set_return_value(&mut self, value: u64) {
+        self.regs[0] = value;
+    }
+    fn syscall_number(&self) -> u64 {
+        self.regs[8]
+    }
+    fn syscall_args(&self) -> [u64; 6] {
+        [
+            self.regs[0],
+            self.regs[1],
+            self.regs[2],
+            self.regs[3],
+            self.regs[4],
+            self.regs[5],
+        ]
+    }
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu", target_arch = "riscv64"))]
+impl Registers for user_regs_struct {
+    fn ip(&self) -> u64 {
+        self.pc
+    }
+    fn set_ip(&mut self, ip: u64) {
+        self.pc = ip;
+    }
+    fn sp(&self) -> u64 {
+        self.sp
+    }
+    fn set_sp(&mut self, sp: u64) {
+        self.sp = sp;
+    }
+    fn return_value(&self) -> u64 {
+        self.a0
+    }
+    fn set_return_value(&mut self, value: u64) {
+        self.a0 = value;
+    }
+    fn syscall_number(&self) -> u64 {
+        self.a7
+    }
+    fn syscall_args(&self) -> [u64; 6] {
+        [self.a0, self.a1, self.a2, self.a3, self.a4, self.a5]
+    }
+}
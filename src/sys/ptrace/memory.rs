@@ -0,0 +1,417 @@
+//! Convenient access to the memory of a traced process.
+
+use super::{read, Request, RequestType};
+use crate::errno::Errno;
+use crate::unistd::Pid;
+use libc::{c_long, c_void};
+use std::ffi::CString;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::mem;
+use std::os::unix::fs::FileExt;
+
+/// A memory transfer with a tracee that stopped short.
+///
+/// The bytes before [`addr`](MemoryError::addr) were transferred, the byte at
+/// `addr` could not be.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MemoryError {
+    addr: usize,
+    transferred: usize,
+    errno: Errno,
+}
+
+impl MemoryError {
+    /// The address in the tracee at which the transfer failed.
+    pub fn addr(&self) -> usize {
+        self.addr
+    }
+
+    /// How many bytes were transferred before the failure.
+    pub fn transferred(&self) -> usize {
+        self.transferred
+    }
+
+    /// The error reported by the kernel, usually `EFAULT` or `EIO` for an
+    /// unmapped address.
+    pub fn errno(&self) -> Errno {
+        self.errno
+    }
+}
+
+impl fmt::Display for MemoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tracee memory transfer failed at {:#x} after {} bytes: {}",
+            self.addr, self.transferred, self.errno
+        )
+    }
+}
+
+impl std::error::Error for MemoryError {}
+
+impl From<MemoryError> for Errno {
+    fn from(err: MemoryError) -> Self {
+        err.errno
+    }
+}
+
+/// Which mechanism [`TraceeMemory`] found to work.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Method {
+    ProcessVm,
+    ProcMem,
+    Ptrace,
+}
+
+/// The memory of a traced process.
+///
+/// Transfers use `process_vm_readv`/`process_vm_writev` where possible, fall
+/// back to `/proc/<pid>/mem` if those are unavailable, and finally to moving
+/// one word at a time with `PTRACE_PEEKDATA`/`PTRACE_POKETEXT`.  Writes to
+/// pages that are mapped read only, like the tracee's code, take one of the
+/// slower paths, which ignore page protection just as a debugger needs.
+///
+/// The word-wise fallback, and writes to read only pages, need the tracee to
+/// be in a ptrace-stop.
+///
+/// # Example
+///
+/// ```no_run
+/// # use nix::sys::ptrace::TraceeMemory;
+/// # use nix::unistd::Pid;
+/// # fn f(pid: Pid, path_addr: usize) -> Result<(), Box<dyn std::error::Error>> {
+/// let mut mem = TraceeMemory::new(pid);
+/// let path = mem.read_cstring(path_addr, 4096)?;
+/// println!("open({:?})", path);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TraceeMemory {
+    pid: Pid,
+    method: Method,
+    proc_mem: Option<File>,
+    page_size: usize,
+}
+
+impl TraceeMemory {
+    /// Access the memory of the process `pid`.
+    pub fn new(pid: Pid) -> Self {
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        TraceeMemory {
+            pid,
+            method: Method::ProcessVm,
+            proc_mem: None,
+            page_size: if page_size > 0 {
+                page_size as usize
+            } else {
+                4096
+            },
+        }
+    }
+
+    /// The process whose memory is accessed.
+    pub fn pid(&self) -> Pid {
+        self.pid
+    }
+
+    /// Fill `buf` with the tracee's memory starting at `addr`.
+    ///
+    /// If the range runs into an unmapped page, the bytes before it are read
+    /// and the error says where the read stopped.
+    pub fn read_bytes(
+        &mut self,
+        addr: usize,
+        buf: &mut [u8],
+    ) -> Result<(), MemoryError> {
+        let mut done = 0;
+        while done < buf.len() {
+            let at = addr.wrapping_add(done);
+            let len = (buf.len() - done).min(self.to_page_end(at));
+            let chunk = &mut buf[done..done + len];
+            match self.read_chunk(at, chunk) {
+                Ok(()) => done += len,
+                Err(errno) => {
+                    return Err(MemoryError {
+                        addr: at,
+                        transferred: done,
+                        errno,
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write `data` to the tracee's memory starting at `addr`.
+    ///
+    /// Read only mappings, such as the tracee's code, can be written to, for
+    /// example to insert breakpoints.  If the range runs into an unmapped
+    /// page, the bytes before it are written and the error says where the
+    /// write stopped.
+    pub fn write_bytes(
+        &mut self,
+        addr: usize,
+        data: &[u8],
+    ) -> Result<(), MemoryError> {
+        let mut done = 0;
+        while done < data.len() {
+            let at = addr.wrapping_add(done);
+            let len = (data.len() - done).min(self.to_page_end(at));
+            match self.write_chunk(at, &data[done..done + len]) {
+                Ok(()) => done += len,
+                Err(errno) => {
+                    return Err(MemoryError {
+                        addr: at,
+                        transferred: done,
+                        errno,
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Read a NUL terminated string starting at `addr`.
+    ///
+    /// At most `max` bytes are read.  If no NUL byte is found among them,
+    /// those `max` bytes are returned.  The string may end just before an
+    /// unmapped page.
+    pub fn read_cstring(
+        &mut self,
+        addr: usize,
+        max: usize,
+    ) -> Result<CString, MemoryError> {
+        let mut bytes = Vec::new();
+        while bytes.len() < max {
+            let at = addr.wrapping_add(bytes.len());
+            let len = (max - bytes.len()).min(self.to_page_end(at));
+            let start = bytes.len();
+            bytes.resize(start + len, 0);
+            if let Err(mut err) = self.read_bytes(at, &mut bytes[start..]) {
+                err.transferred += start;
+                return Err(err);
+            }
+            if let Some(nul) = bytes[start..].iter().position(|&b| b == 0) {
+                bytes.truncate(start + nul);
+                break;
+            }
+        }
+        // `bytes` contains no NUL byte
+        Ok(CString::new(bytes).unwrap())
+    }
+
+    /// Read a `T` from the tracee's memory at `addr`.
+    ///
+    /// # Safety
+    ///
+    /// Any bit pattern read from the tracee must be a valid `T`.
+    pub unsafe fn read_struct<T>(
+        &mut self,
+        addr: usize,
+    ) -> Result<T, MemoryError> {
+        let mut value = mem::MaybeUninit::<T>::zeroed();
+        let buf = std::slice::from_raw_parts_mut(
+            value.as_mut_ptr().cast::<u8>(),
+            mem::size_of::<T>(),
+        );
+        self.read_bytes(addr, buf)?;
+        Ok(value.assume_init())
+    }
+
+    /// The number of bytes from `addr` to the end of its page.
+    fn to_page_end(&self, addr: usize) -> usize {
+        self.page_size - addr % self.page_size
+    }
+
+    /// Read a range that does not cross a page boundary.
+    fn read_chunk(&mut self, addr: usize, buf: &mut [u8]) -> Result<(), Errno> {
+        if self.method == Method::ProcessVm {
+            match self.process_vm(addr, buf.as_mut_ptr(), buf.len(), false) {
+                Err(Errno::ENOSYS) | Err(Errno::EPERM) => {
+                    self.method = Method::ProcMem
+                }
+                res => return res,
+            }
+        }
+        if self.method == Method::ProcMem {
+            match self.open_proc_mem() {
+                Ok(file) => return read_proc_mem(file, addr, buf),
+                Err(_) => self.method = Method::Ptrace,
+            }
+        }
+        self.peek(addr, buf)
+    }
+
+    /// Write a range that does not cross a page boundary.
+    fn write_chunk(&mut self, addr: usize, data: &[u8]) -> Result<(), Errno> {
+        if self.method == Method::ProcessVm {
+            let ptr = data.as_ptr() as *mut u8;
+            match self.process_vm(addr, ptr, data.len(), true) {
+                Ok(()) => return Ok(()),
+                Err(Errno::ENOSYS) | Err(Errno::EPERM) => {
+                    self.method = Method::ProcMem
+                }
+                // The page is unmapped or read only.  Only the paths below
+                // can tell those apart and write to read only pages.
+                Err(Errno::EFAULT) => (),
+                Err(e) => return Err(e),
+            }
+        }
+        if self.method != Method::Ptrace {
+            let method = self.method;
+            match self.open_proc_mem() {
+                Ok(file) => match write_proc_mem(file, addr, data) {
+                    // Only opened for reading
+                    Err(Errno::EBADF) => (),
+                    res => return res,
+                },
+                Err(_) if method == Method::ProcMem => {
+                    self.method = Method::Ptrace
+                }
+                Err(_) => (),
+            }
+        }
+        self.poke(addr, data)
+    }
+
+    fn process_vm(
+        &self,
+        addr: usize,
+        local: *mut u8,
+        len: usize,
+        write: bool,
+    ) -> Result<(), Errno> {
+        let local = libc::iovec {
+            iov_base: local.cast(),
+            iov_len: len,
+        };
+        let remote = libc::iovec {
+            iov_base: addr as *mut c_void,
+            iov_len: len,
+        };
+        let res = unsafe {
+            if write {
+                libc::process_vm_writev(
+                    self.pid.into(),
+                    &local,
+                    1,
+                    &remote,
+                    1,
+                    0,
+                )
+            } else {
+                libc::process_vm_readv(
+                    self.pid.into(),
+                    &local,
+                    1,
+                    &remote,
+                    1,
+                    0,
+                )
+            }
+        };
+        // The range is within one page, so it is transferred all or nothing.
+        match Errno::result(res)? as usize {
+            n if n == len => Ok(()),
+            _ => Err(Errno::EFAULT),
+        }
+    }
+
+    fn open_proc_mem(&mut self) -> io::Result<&File> {
+        if self.proc_mem.is_none() {
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(format!("/proc/{}/mem", self.pid))
+                .or_else(|_| File::open(format!("/proc/{}/mem", self.pid)))?;
+            self.proc_mem = Some(file);
+        }
+        Ok(self.proc_mem.as_ref().unwrap())
+    }
+
+    /// Read with `PTRACE_PEEKDATA`, one aligned word at a time.
+    fn peek(&self, addr: usize, buf: &mut [u8]) -> Result<(), Errno> {
+        const WORD: usize = mem::size_of::<c_long>();
+        let mut done = 0;
+        while done < buf.len() {
+            let at = addr + done;
+            let offset = at % WORD;
+            let word = read(self.pid, (at - offset) as *mut c_void)?;
+            let bytes = word.to_ne_bytes();
+            let len = (WORD - offset).min(buf.len() - done);
+            buf[done..done + len].copy_from_slice(&bytes[offset..offset + len]);
+            done += len;
+        }
+        Ok(())
+    }
+
+    /// Write with `PTRACE_POKETEXT`, one aligned word at a time, merging
+    /// partial words with what is already there.
+    fn poke(&self, addr: usize, data: &[u8]) -> Result<(), Errno> {
+        const WORD: usize = mem::size_of::<c_long>();
+        let mut done = 0;
+        while done < data.len() {
+            let at = addr + done;
+            let offset = at % WORD;
+            let aligned = (at - offset) as *mut c_void;
+            let len = (WORD - offset).min(data.len() - done);
+            let mut bytes = [0u8; WORD];
+            if len < WORD {
+                bytes = read(self.pid, aligned)?.to_ne_bytes();
+            }
+            bytes[offset..offset + len]
+                .copy_from_slice(&data[done..done + len]);
+            let res = unsafe {
+                libc::ptrace(
+                    Request::PTRACE_POKETEXT as RequestType,
+                    libc::pid_t::from(self.pid),
+                    aligned,
+                    c_long::from_ne_bytes(bytes) as *mut c_void,
+                )
+            };
+            Errno::result(res)?;
+            done += len;
+        }
+        Ok(())
+    }
+}
+
+fn read_proc_mem(
+    file: &File,
+    addr: usize,
+    buf: &mut [u8],
+) -> Result<(), Errno> {
+    let mut done = 0;
+    while done < buf.len() {
+        match file.read_at(&mut buf[done..], (addr + done) as u64) {
+            Ok(0) => return Err(Errno::EIO),
+            Ok(n) => done += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(e) => return Err(io_errno(e)),
+        }
+    }
+    Ok(())
+}
+
+fn write_proc_mem(file: &File, addr: usize, data: &[u8]) -> Result<(), Errno> {
+    let mut done = 0;
+    while done < data.len() {
+        match file.write_at(&data[done..], (addr + done) as u64) {
+            Ok(0) => return Err(Errno::EIO),
+            Ok(n) => done += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(e) => return Err(io_errno(e)),
+        }
+    }
+    Ok(())
+}
+
+fn io_errno(err: io::Error) -> Errno {
+    err.raw_os_error()
+        .map(Errno::from_i32)
+        .unwrap_or(Errno::EIO)
+}
@@ -6,6 +6,59 @@ mod linux;
 #[cfg(any(target_os = "android", target_os = "linux"))]
 pub use self::linux::*;
 
+#[cfg(all(
+    any(target_os = "android", target_os = "linux"),
+    not(target_env = "uclibc")
+))]
+mod memory;
+
+#[cfg(all(
+    any(target_os = "android", target_os = "linux"),
+    not(target_env = "uclibc")
+))]
+pub use self::memory::*;
+
+#[cfg(all(
+    any(target_os = "android", target_os = "linux"),
+    any(
+        target_arch = "aarch64",
+        target_arch = "riscv64",
+        target_arch = "x86",
+        all(target_arch = "x86_64", target_pointer_width = "64")
+    )
+))]
+mod sysno;
+
+#[cfg(all(
+    any(target_os = "android", target_os = "linux"),
+    any(
+        target_arch = "aarch64",
+        target_arch = "riscv64",
+        target_arch = "x86",
+        all(target_arch = "x86_64", target_pointer_width = "64")
+    )
+))]
+pub use self::sysno::*;
+
+#[cfg(all(
+    target_os = "linux",
+    not(any(target_arch = "mips", target_arch = "mips64"))
+))]
+mod tracer;
+
+#[cfg(all(
+    target_os = "linux",
+    not(any(target_arch = "mips", target_arch = "mips64"))
+))]
+pub use self::tracer::*;
+
+#[cfg(all(
+    target_os = "linux",
+    target_env = "gnu",
+    any(target_arch = "aarch64", target_arch = "x86", target_arch = "x86_64")
+))]
+pub mod watchpoint;
+
 #[cfg(any(
     target_os = "dragonfly",
     target_os = "freebsd",
@@ -0,0 +1,310 @@
+// The system call table of aarch64 Linux 6.4, from asm-generic/unistd.h.
+
+sysno! {
+    io_setup = 0, 2;
+    io_destroy = 1, 1;
+    io_submit = 2, 3;
+    io_cancel = 3, 3;
+    io_getevents = 4, 5;
+    setxattr = 5, 5;
+    lsetxattr = 6, 5;
+    fsetxattr = 7, 5;
+    getxattr = 8, 4;
+    lgetxattr = 9, 4;
+    fgetxattr = 10, 4;
+    listxattr = 11, 3;
+    llistxattr = 12, 3;
+    flistxattr = 13, 3;
+    removexattr = 14, 2;
+    lremovexattr = 15, 2;
+    fremovexattr = 16, 2;
+    getcwd = 17, 2;
+    lookup_dcookie = 18, 3;
+    eventfd2 = 19, 2;
+    epoll_create1 = 20, 1;
+    epoll_ctl = 21, 4;
+    epoll_pwait = 22, 6;
+    dup = 23, 1;
+    dup3 = 24, 3;
+    fcntl = 25, 3;
+    inotify_init1 = 26, 1;
+    inotify_add_watch = 27, 3;
+    inotify_rm_watch = 28, 2;
+    ioctl = 29, 3;
+    ioprio_set = 30, 3;
+    ioprio_get = 31, 2;
+    flock = 32, 2;
+    mknodat = 33, 4;
+    mkdirat = 34, 3;
+    unlinkat = 35, 3;
+    symlinkat = 36, 3;
+    linkat = 37, 5;
+    renameat = 38, 4;
+    umount2 = 39, 2;
+    mount = 40, 5;
+    pivot_root = 41, 2;
+    nfsservctl = 42, 0;
+    statfs = 43, 2;
+    fstatfs = 44, 2;
+    truncate = 45, 2;
+    ftruncate = 46, 2;
+    fallocate = 47, 4;
+    faccessat = 48, 3;
+    chdir = 49, 1;
+    fchdir = 50, 1;
+    chroot = 51, 1;
+    fchmod = 52, 2;
+    fchmodat = 53, 3;
+    fchownat = 54, 5;
+    fchown = 55, 3;
+    openat = 56, 4;
+    close = 57, 1;
+    vhangup = 58, 0;
+    pipe2 = 59, 2;
+    quotactl = 60, 4;
+    getdents64 = 61, 3;
+    lseek = 62, 3;
+    read = 63, 3;
+    write = 64, 3;
+    readv = 65, 3;
+    writev = 66, 3;
+    pread64 = 67, 4;
+    pwrite64 = 68, 4;
+    preadv = 69, 5;
+    pwritev = 70, 5;
+    sendfile = 71, 4;
+    pselect6 = 72, 6;
+    ppoll = 73, 5;
+    signalfd4 = 74, 4;
+    vmsplice = 75, 4;
+    splice = 76, 6;
+    tee = 77, 4;
+    readlinkat = 78, 4;
+    newfstatat = 79, 4;
+    fstat = 80, 2;
+    sync = 81, 0;
+    fsync = 82, 1;
+    fdatasync = 83, 1;
+    sync_file_range = 84, 4;
+    timerfd_create = 85, 2;
+    timerfd_settime = 86, 4;
+    timerfd_gettime = 87, 2;
+    utimensat = 88, 4;
+    acct = 89, 1;
+    capget = 90, 2;
+    capset = 91, 2;
+    personality = 92, 1;
+    exit = 93, 1;
+    exit_group = 94, 1;
+    waitid = 95, 5;
+    set_tid_address = 96, 1;
+    unshare = 97, 1;
+    futex = 98, 6;
+    set_robust_list = 99, 2;
+    get_robust_list = 100, 3;
+    nanosleep = 101, 2;
+    getitimer = 102, 2;
+    setitimer = 103, 3;
+    kexec_load = 104, 4;
+    init_module = 105, 3;
+    delete_module = 106, 2;
+    timer_create = 107, 3;
+    timer_gettime = 108, 2;
+    timer_getoverrun = 109, 1;
+    timer_settime = 110, 4;
+    timer_delete = 111, 1;
+    clock_settime = 112, 2;
+    clock_gettime = 113, 2;
+    clock_getres = 114, 2;
+    clock_nanosleep = 115, 4;
+    syslog = 116, 3;
+    ptrace = 117, 4;
+    sched_setparam = 118, 2;
+    sched_setscheduler = 119, 3;
+    sched_getscheduler = 120, 1;
+    sched_getparam = 121, 2;
+    sched_setaffinity = 122, 3;
+    sched_getaffinity = 123, 3;
+    sched_yield = 124, 0;
+    sched_get_priority_max = 125, 1;
+    sched_get_priority_min = 126, 1;
+    sched_rr_get_interval = 127, 2;
+    restart_syscall = 128, 0;
+    kill = 129, 2;
+    tkill = 130, 2;
+    tgkill = 131, 3;
+    sigaltstack = 132, 2;
+    rt_sigsuspend = 133, 2;
+    rt_sigaction = 134, 4;
+    rt_sigprocmask = 135, 4;
+    rt_sigpending = 136, 2;
+    rt_sigtimedwait = 137, 4;
+    rt_sigqueueinfo = 138, 3;
+    rt_sigreturn = 139, 0;
+    setpriority = 140, 3;
+    getpriority = 141, 2;
+    reboot = 142, 4;
+    setregid = 143, 2;
+    setgid = 144, 1;
+    setreuid = 145, 2;
+    setuid = 146, 1;
+    setresuid = 147, 3;
+    getresuid = 148, 3;
+    setresgid = 149, 3;
+    getresgid = 150, 3;
+    setfsuid = 151, 1;
+    setfsgid = 152, 1;
+    times = 153, 1;
+    setpgid = 154, 2;
+    getpgid = 155, 1;
+    getsid = 156, 1;
+    setsid = 157, 0;
+    getgroups = 158, 2;
+    setgroups = 159, 2;
+    uname = 160, 1;
+    sethostname = 161, 2;
+    setdomainname = 162, 2;
+    getrlimit = 163, 2;
+    setrlimit = 164, 2;
+    getrusage = 165, 2;
+    umask = 166, 1;
+    prctl = 167, 5;
+    getcpu = 168, 3;
+    gettimeofday = 169, 2;
+    settimeofday = 170, 2;
+    adjtimex = 171, 1;
+    getpid = 172, 0;
+    getppid = 173, 0;
+    getuid = 174, 0;
+    geteuid = 175, 0;
+    getgid = 176, 0;
+    getegid = 177, 0;
+    gettid = 178, 0;
+    sysinfo = 179, 1;
+    mq_open = 180, 4;
+    mq_unlink = 181, 1;
+    mq_timedsend = 182, 5;
+    mq_timedreceive = 183, 5;
+    mq_notify = 184, 2;
+    mq_getsetattr = 185, 3;
+    msgget = 186, 2;
+    msgctl = 187, 3;
+    msgrcv = 188, 5;
+    msgsnd = 189, 4;
+    semget = 190, 3;
+    semctl = 191, 4;
+    semtimedop = 192, 4;
+    semop = 193, 3;
+    shmget = 194, 3;
+    shmctl = 195, 3;
+    shmat = 196, 3;
+    shmdt = 197, 1;
+    socket = 198, 3;
+    socketpair = 199, 4;
+    bind = 200, 3;
+    listen = 201, 2;
+    accept = 202, 3;
+    connect = 203, 3;
+    getsockname = 204, 3;
+    getpeername = 205, 3;
+    sendto = 206, 6;
+    recvfrom = 207, 6;
+    setsockopt = 208, 5;
+    getsockopt = 209, 5;
+    shutdown = 210, 2;
+    sendmsg = 211, 3;
+    recvmsg = 212, 3;
+    readahead = 213, 3;
+    brk = 214, 1;
+    munmap = 215, 2;
+    mremap = 216, 5;
+    add_key = 217, 5;
+    request_key = 218, 4;
+    keyctl = 219, 5;
+    clone = 220, 5;
+    execve = 221, 3;
+    mmap = 222, 6;
+    fadvise64 = 223, 4;
+    swapon = 224, 2;
+    swapoff = 225, 1;
+    mprotect = 226, 3;
+    msync = 227, 3;
+    mlock = 228, 2;
+    munlock = 229, 2;
+    mlockall = 230, 1;
+    munlockall = 231, 0;
+    mincore = 232, 3;
+    madvise = 233, 3;
+    remap_file_pages = 234, 5;
+    mbind = 235, 6;
+    get_mempolicy = 236, 5;
+    set_mempolicy = 237, 3;
+    migrate_pages = 238, 4;
+    move_pages = 239, 6;
+    rt_tgsigqueueinfo = 240, 4;
+    perf_event_open = 241, 5;
+    accept4 = 242, 4;
+    recvmmsg = 243, 5;
+    wait4 = 260, 4;
+    prlimit64 = 261, 4;
+    fanotify_init = 262, 2;
+    fanotify_mark = 263, 5;
+    name_to_handle_at = 264, 5;
+    open_by_handle_at = 265, 3;
+    clock_adjtime = 266, 2;
+    syncfs = 267, 1;
+    setns = 268, 2;
+    sendmmsg = 269, 4;
+    process_vm_readv = 270, 6;
+    process_vm_writev = 271, 6;
+    kcmp = 272, 5;
+    finit_module = 273, 3;
+    sched_setattr = 274, 3;
+    sched_getattr = 275, 4;
+    renameat2 = 276, 5;
+    seccomp = 277, 3;
+    getrandom = 278, 3;
+    memfd_create = 279, 2;
+    bpf = 280, 3;
+    execveat = 281, 5;
+    userfaultfd = 282, 1;
+    membarrier = 283, 3;
+    mlock2 = 284, 3;
+    copy_file_range = 285, 6;
+    preadv2 = 286, 6;
+    pwritev2 = 287, 6;
+    pkey_mprotect = 288, 4;
+    pkey_alloc = 289, 2;
+    pkey_free = 290, 1;
+    statx = 291, 5;
+    io_pgetevents = 292, 6;
+    rseq = 293, 4;
+    kexec_file_load = 294, 5;
+    pidfd_send_signal = 424, 4;
+    io_uring_setup = 425, 2;
+    io_uring_enter = 426, 6;
+    io_uring_register = 427, 4;
+    open_tree = 428, 3;
+    move_mount = 429, 5;
+    fsopen = 430, 2;
+    fsconfig = 431, 5;
+    fsmount = 432, 3;
+    fspick = 433, 3;
+    pidfd_open = 434, 2;
+    clone3 = 435, 2;
+    close_range = 436, 3;
+    openat2 = 437, 4;
+    pidfd_getfd = 438, 3;
+    faccessat2 = 439, 4;
+    process_madvise = 440, 5;
+    epoll_pwait2 = 441, 6;
+    mount_setattr = 442, 5;
+    quotactl_fd = 443, 4;
+    landlock_create_ruleset = 444, 3;
+    landlock_add_rule = 445, 4;
+    landlock_restrict_self = 446, 2;
+    memfd_secret = 447, 1;
+    process_mrelease = 448, 2;
+    futex_waitv = 449, 5;
+    set_mempolicy_home_node = 450, 4;
+}
@@ -0,0 +1,382 @@
+//! System call numbers, and formatting of the system calls tracees make.
+
+use crate::errno::Errno;
+use crate::Result;
+use libc::c_long;
+use std::fmt;
+use std::str::FromStr;
+
+macro_rules! sysno {
+    ($($name:ident = $nr:literal, $arity:literal;)*) => {
+        /// A system call of the target architecture.
+        ///
+        /// The variants are named after the entries of the kernel's system
+        /// call table, which are not always named after the C library
+        /// function wrapping them.  The set of variants differs between
+        /// architectures.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// # use nix::sys::ptrace::Sysno;
+        /// let sysno: Sysno = "openat".parse().unwrap();
+        /// assert_eq!(sysno.name(), "openat");
+        /// assert_eq!(sysno.arity(), 4);
+        /// assert_eq!(sysno.as_raw(), libc::SYS_openat);
+        /// ```
+        #[allow(missing_docs, non_camel_case_types)]
+        #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        #[non_exhaustive]
+        pub enum Sysno {
+            $($name = $nr,)*
+        }
+
+        impl Sysno {
+            pub(super) const ALL: &'static [Sysno] = &[$(Sysno::$name,)*];
+
+            /// The name of the system call, as in the kernel's system call
+            /// table.
+            pub fn name(self) -> &'static str {
+                let name = match self {
+                    $(Sysno::$name => stringify!($name),)*
+                };
+                name.trim_start_matches("r#")
+            }
+
+            /// The number of arguments the system call takes.
+            ///
+            /// Where a 64-bit argument is passed in two registers, as on
+            /// x86, it counts twice.
+            pub const fn arity(self) -> usize {
+                match self {
+                    $(Sysno::$name => $arity,)*
+                }
+            }
+        }
+
+        impl std::convert::TryFrom<u64> for Sysno {
+            type Error = $crate::errno::Errno;
+
+            fn try_from(nr: u64) -> $crate::Result<Self> {
+                match nr {
+                    $($nr => Ok(Sysno::$name),)*
+                    _ => Err($crate::errno::Errno::EINVAL),
+                }
+            }
+        }
+    };
+}
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64;
+#[cfg(target_arch = "aarch64")]
+pub use self::aarch64::*;
+
+#[cfg(target_arch = "riscv64")]
+mod riscv64;
+#[cfg(target_arch = "riscv64")]
+pub use self::riscv64::*;
+
+#[cfg(target_arch = "x86")]
+mod x86;
+#[cfg(target_arch = "x86")]
+pub use self::x86::*;
+
+#[cfg(target_arch = "x86_64")]
+mod x86_64;
+#[cfg(target_arch = "x86_64")]
+pub use self::x86_64::*;
+
+impl Sysno {
+    /// The system call number, as passed to `syscall`.
+    pub const fn as_raw(self) -> c_long {
+        self as c_long
+    }
+
+    /// Iterate through all system calls of the target architecture, in
+    /// ascending order of their numbers.
+    pub fn iterator() -> impl Iterator<Item = Sysno> {
+        Sysno::ALL.iter().copied()
+    }
+}
+
+impl FromStr for Sysno {
+    type Err = Errno;
+
+    fn from_str(s: &str) -> Result<Sysno> {
+        Sysno::iterator()
+            .find(|sysno| sysno.name() == s)
+            .ok_or(Errno::EINVAL)
+    }
+}
+
+impl AsRef<str> for Sysno {
+    fn as_ref(&self) -> &str {
+        self.name()
+    }
+}
+
+impl fmt::Display for Sysno {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(not(target_env = "uclibc"))]
+pub use self::format::format_syscall_entry;
+
+#[cfg(not(target_env = "uclibc"))]
+mod format {
+    use super::Sysno;
+    use crate::sys::ptrace::TraceeMemory;
+    use crate::unistd::Pid;
+    use libc::{c_int, c_long, c_ulong};
+    use std::fmt::Write;
+
+    /// How a system call argument is shown.
+    #[derive(Clone, Copy)]
+    enum Arg {
+        Int,
+        Uint,
+        Hex,
+        Ptr,
+        Fd,
+        DirFd,
+        Path,
+        OpenFlags,
+        AtFlags,
+        Mode,
+        Prot,
+        MapFlags,
+        Signal,
+    }
+
+    fn arg_kinds(sysno: Sysno) -> &'static [Arg] {
+        use self::Arg::*;
+
+        match sysno.name() {
+            "read" | "write" => &[Fd, Ptr, Uint],
+            "pread64" | "pwrite64" => &[Fd, Ptr, Uint, Int],
+            "readv" | "writev" => &[Fd, Ptr, Int],
+            "close" | "fsync" | "fdatasync" | "fchdir" | "dup" | "syncfs" => {
+                &[Fd]
+            }
+            "dup2" => &[Fd, Fd],
+            "dup3" => &[Fd, Fd, OpenFlags],
+            "lseek" => &[Fd, Int, Int],
+            "ioctl" | "fcntl" | "fcntl64" => &[Fd, Hex, Hex],
+            "fstat" | "fstat64" | "fstatfs" => &[Fd, Ptr],
+            "ftruncate" => &[Fd, Int],
+            "fchmod" => &[Fd, Mode],
+            "getdents" | "getdents64" => &[Fd, Ptr, Uint],
+            "open" => &[Path, OpenFlags, Mode],
+            "openat" => &[DirFd, Path, OpenFlags, Mode],
+            "creat" | "mkdir" | "chmod" => &[Path, Mode],
+            "access" => &[Path, Int],
+            "stat" | "stat64" | "lstat" | "lstat64" | "statfs" => &[Path, Ptr],
+            "chdir" | "chroot" | "rmdir" | "unlink" | "acct" | "swapoff" => {
+                &[Path]
+            }
+            "truncate" => &[Path, Int],
+            "readlink" => &[Path, Ptr, Uint],
+            "rename" | "link" | "symlink" => &[Path, Path],
+            "chown" | "lchown" => &[Path, Int, Int],
+            "execve" => &[Path, Ptr, Ptr],
+            "execveat" => &[DirFd, Path, Ptr, Ptr, AtFlags],
+            "mkdirat" => &[DirFd, Path, Mode],
+            "mknodat" => &[DirFd, Path, Mode, Hex],
+            "unlinkat" => &[DirFd, Path, AtFlags],
+            "faccessat" => &[DirFd, Path, Int],
+            "faccessat2" => &[DirFd, Path, Int, AtFlags],
+            "fchmodat" => &[DirFd, Path, Mode],
+            "fchownat" => &[DirFd, Path, Int, Int, AtFlags],
+            "newfstatat" | "fstatat64" => &[DirFd, Path, Ptr, AtFlags],
+            "statx" => &[DirFd, Path, AtFlags, Hex, Ptr],
+            "readlinkat" => &[DirFd, Path, Ptr, Uint],
+            "renameat" => &[DirFd, Path, DirFd, Path],
+            "renameat2" => &[DirFd, Path, DirFd, Path, Hex],
+            "linkat" => &[DirFd, Path, DirFd, Path, AtFlags],
+            "symlinkat" => &[Path, DirFd, Path],
+            "utimensat" => &[DirFd, Path, Ptr, AtFlags],
+            "mmap" | "mmap2" => &[Ptr, Uint, Prot, MapFlags, Fd, Hex],
+            "mprotect" => &[Ptr, Uint, Prot],
+            "munmap" | "mlock" | "munlock" => &[Ptr, Uint],
+            "brk" => &[Ptr],
+            "kill" | "tkill" => &[Int, Signal],
+            "tgkill" => &[Int, Int, Signal],
+            "exit" | "exit_group" => &[Int],
+            _ => &[],
+        }
+    }
+
+    /// Format the entry to system call `sysno` with the arguments `args`
+    /// like `strace` does, as in
+    /// `openat(AT_FDCWD, "/etc/passwd", O_RDONLY | O_CLOEXEC)`.
+    ///
+    /// Strings the arguments point to are read from the memory of the tracee
+    /// `pid`, which must be stopped.  File descriptors, modes and flags are
+    /// decoded for common system calls, using [`OFlag`], [`MapFlags`] and
+    /// friends when the features providing them are enabled.  Other
+    /// arguments are shown in hexadecimal.  Only as many arguments as the
+    /// system call takes are shown.
+    ///
+    /// `sysno` and `args` typically come from
+    /// [`get_syscall_info`](crate::sys::ptrace::get_syscall_info) or from
+    /// [`Registers`](crate::sys::ptrace::Registers).
+    ///
+    /// [`OFlag`]: crate::fcntl::OFlag
+    /// [`MapFlags`]: crate::sys::mman::MapFlags
+    pub fn format_syscall_entry(
+        pid: Pid,
+        sysno: Sysno,
+        args: &[u64; 6],
+    ) -> String {
+        let mut memory = TraceeMemory::new(pid);
+        let kinds = arg_kinds(sysno);
+        let mut s = format!("{}(", sysno);
+        for (i, &arg) in args.iter().take(sysno.arity()).enumerate() {
+            if i > 0 {
+                s.push_str(", ");
+            }
+            let kind = kinds.get(i).copied().unwrap_or(Arg::Hex);
+            format_arg(&mut s, &mut memory, kind, arg);
+        }
+        s.push(')');
+        s
+    }
+
+    fn format_arg(
+        s: &mut String,
+        memory: &mut TraceeMemory,
+        kind: Arg,
+        arg: u64,
+    ) {
+        // Arguments are register sized.
+        let int = arg as c_long;
+        let uint = arg as c_ulong;
+        let _ = match kind {
+            Arg::Int => write!(s, "{}", int),
+            Arg::Uint => write!(s, "{}", uint),
+            Arg::Fd => write!(s, "{}", int as c_int),
+            Arg::DirFd if int as c_int == libc::AT_FDCWD => {
+                write!(s, "AT_FDCWD")
+            }
+            Arg::DirFd => write!(s, "{}", int as c_int),
+            Arg::Ptr | Arg::Path if uint == 0 => write!(s, "NULL"),
+            Arg::Hex | Arg::Ptr => write!(s, "{:#x}", uint),
+            Arg::Mode => write!(s, "{:#o}", uint),
+            Arg::Path => {
+                let max = libc::PATH_MAX as usize;
+                match memory.read_cstring(uint as usize, max) {
+                    Ok(path) => write!(
+                        s,
+                        "{:?}",
+                        String::from_utf8_lossy(path.as_bytes())
+                    ),
+                    Err(_) => write!(s, "{:#x}", uint),
+                }
+            }
+            Arg::OpenFlags => write!(s, "{}", open_flags(int as c_int)),
+            Arg::AtFlags => write!(s, "{}", at_flags(int as c_int)),
+            Arg::Prot => write!(s, "{}", prot_flags(int as c_int)),
+            Arg::MapFlags => write!(s, "{}", map_flags(int as c_int)),
+            Arg::Signal => write!(s, "{}", signal(int as c_int)),
+        };
+    }
+
+    /// Join the names of flags, followed by the bits that have no name.
+    fn join(mut names: Vec<String>, unknown: c_int) -> String {
+        if unknown != 0 {
+            names.push(format!("{:#x}", unknown));
+        }
+        if names.is_empty() {
+            "0".to_owned()
+        } else {
+            names.join(" | ")
+        }
+    }
+
+    #[cfg(any(feature = "fs", feature = "term"))]
+    fn open_flags(flags: c_int) -> String {
+        use crate::fcntl::OFlag;
+
+        // O_RDONLY has no bits of its own, so the access mode is named
+        // separately.
+        let mode = match flags & libc::O_ACCMODE {
+            libc::O_RDONLY => "O_RDONLY",
+            libc::O_WRONLY => "O_WRONLY",
+            libc::O_RDWR => "O_RDWR",
+            _ => return format!("{:#x}", flags),
+        };
+        let rest = flags & !libc::O_ACCMODE;
+        let known = OFlag::from_bits_truncate(rest);
+        let mut names = vec![mode.to_owned()];
+        if !known.is_empty() {
+            names.push(format!("{:?}", known));
+        }
+        join(names, rest & !known.bits())
+    }
+
+    #[cfg(not(any(feature = "fs", feature = "term")))]
+    fn open_flags(flags: c_int) -> String {
+        format!("{:#x}", flags)
+    }
+
+    fn at_flags(flags: c_int) -> String {
+        use crate::fcntl::AtFlags;
+
+        let known = AtFlags::from_bits_truncate(flags);
+        let mut names = Vec::new();
+        if !known.is_empty() {
+            names.push(format!("{:?}", known));
+        }
+        join(names, flags & !known.bits())
+    }
+
+    #[cfg(feature = "mman")]
+    fn prot_flags(flags: c_int) -> String {
+        use crate::sys::mman::ProtFlags;
+
+        let known = ProtFlags::from_bits_truncate(flags);
+        // PROT_NONE names the empty set.
+        join(vec![format!("{:?}", known)], flags & !known.bits())
+    }
+
+    #[cfg(not(feature = "mman"))]
+    fn prot_flags(flags: c_int) -> String {
+        format!("{:#x}", flags)
+    }
+
+    #[cfg(feature = "mman")]
+    fn map_flags(flags: c_int) -> String {
+        use crate::sys::mman::MapFlags;
+
+        let known = MapFlags::from_bits_truncate(flags);
+        let mut names = Vec::new();
+        if !known.is_empty() {
+            names.push(format!("{:?}", known));
+        }
+        join(names, flags & !known.bits())
+    }
+
+    #[cfg(not(feature = "mman"))]
+    fn map_flags(flags: c_int) -> String {
+        format!("{:#x}", flags)
+    }
+
+    #[cfg(feature = "signal")]
+    fn signal(signal: c_int) -> String {
+        use crate::sys::signal::Signal;
+        use std::convert::TryFrom;
+
+        match Signal::try_from(signal) {
+            Ok(signal) => signal.as_str().to_owned(),
+            Err(_) => signal.to_string(),
+        }
+    }
+
+    #[cfg(not(feature = "signal"))]
+    fn signal(signal: c_int) -> String {
+        signal.to_string()
+    }
+}
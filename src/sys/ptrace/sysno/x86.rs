@@ -0,0 +1,444 @@
+// The system call table of x86 Linux 6.4, from unistd_32.h.
+
+sysno! {
+    restart_syscall = 0, 0;
+    exit = 1, 1;
+    fork = 2, 0;
+    read = 3, 3;
+    write = 4, 3;
+    open = 5, 3;
+    close = 6, 1;
+    waitpid = 7, 3;
+    creat = 8, 2;
+    link = 9, 2;
+    unlink = 10, 1;
+    execve = 11, 3;
+    chdir = 12, 1;
+    time = 13, 1;
+    mknod = 14, 3;
+    chmod = 15, 2;
+    lchown = 16, 3;
+    r#break = 17, 0;
+    oldstat = 18, 2;
+    lseek = 19, 3;
+    getpid = 20, 0;
+    mount = 21, 5;
+    umount = 22, 1;
+    setuid = 23, 1;
+    getuid = 24, 0;
+    stime = 25, 1;
+    ptrace = 26, 4;
+    alarm = 27, 1;
+    oldfstat = 28, 2;
+    pause = 29, 0;
+    utime = 30, 2;
+    stty = 31, 0;
+    gtty = 32, 0;
+    access = 33, 2;
+    nice = 34, 1;
+    ftime = 35, 0;
+    sync = 36, 0;
+    kill = 37, 2;
+    rename = 38, 2;
+    mkdir = 39, 2;
+    rmdir = 40, 1;
+    dup = 41, 1;
+    pipe = 42, 1;
+    times = 43, 1;
+    prof = 44, 0;
+    brk = 45, 1;
+    setgid = 46, 1;
+    getgid = 47, 0;
+    signal = 48, 2;
+    geteuid = 49, 0;
+    getegid = 50, 0;
+    acct = 51, 1;
+    umount2 = 52, 2;
+    lock = 53, 0;
+    ioctl = 54, 3;
+    fcntl = 55, 3;
+    mpx = 56, 0;
+    setpgid = 57, 2;
+    ulimit = 58, 0;
+    oldolduname = 59, 1;
+    umask = 60, 1;
+    chroot = 61, 1;
+    ustat = 62, 2;
+    dup2 = 63, 2;
+    getppid = 64, 0;
+    getpgrp = 65, 0;
+    setsid = 66, 0;
+    sigaction = 67, 3;
+    sgetmask = 68, 0;
+    ssetmask = 69, 1;
+    setreuid = 70, 2;
+    setregid = 71, 2;
+    sigsuspend = 72, 1;
+    sigpending = 73, 1;
+    sethostname = 74, 2;
+    setrlimit = 75, 2;
+    getrlimit = 76, 2;
+    getrusage = 77, 2;
+    gettimeofday = 78, 2;
+    settimeofday = 79, 2;
+    getgroups = 80, 2;
+    setgroups = 81, 2;
+    select = 82, 1;
+    symlink = 83, 2;
+    oldlstat = 84, 2;
+    readlink = 85, 3;
+    uselib = 86, 1;
+    swapon = 87, 2;
+    reboot = 88, 4;
+    readdir = 89, 3;
+    mmap = 90, 1;
+    munmap = 91, 2;
+    truncate = 92, 2;
+    ftruncate = 93, 2;
+    fchmod = 94, 2;
+    fchown = 95, 3;
+    getpriority = 96, 2;
+    setpriority = 97, 3;
+    profil = 98, 0;
+    statfs = 99, 2;
+    fstatfs = 100, 2;
+    ioperm = 101, 3;
+    socketcall = 102, 2;
+    syslog = 103, 3;
+    setitimer = 104, 3;
+    getitimer = 105, 2;
+    stat = 106, 2;
+    lstat = 107, 2;
+    fstat = 108, 2;
+    olduname = 109, 1;
+    iopl = 110, 1;
+    vhangup = 111, 0;
+    idle = 112, 0;
+    vm86old = 113, 1;
+    wait4 = 114, 4;
+    swapoff = 115, 1;
+    sysinfo = 116, 1;
+    ipc = 117, 6;
+    fsync = 118, 1;
+    sigreturn = 119, 0;
+    clone = 120, 5;
+    setdomainname = 121, 2;
+    uname = 122, 1;
+    modify_ldt = 123, 3;
+    adjtimex = 124, 1;
+    mprotect = 125, 3;
+    sigprocmask = 126, 3;
+    create_module = 127, 0;
+    init_module = 128, 3;
+    delete_module = 129, 2;
+    get_kernel_syms = 130, 0;
+    quotactl = 131, 4;
+    getpgid = 132, 1;
+    fchdir = 133, 1;
+    bdflush = 134, 2;
+    sysfs = 135, 3;
+    personality = 136, 1;
+    afs_syscall = 137, 0;
+    setfsuid = 138, 1;
+    setfsgid = 139, 1;
+    _llseek = 140, 5;
+    getdents = 141, 3;
+    _newselect = 142, 5;
+    flock = 143, 2;
+    msync = 144, 3;
+    readv = 145, 3;
+    writev = 146, 3;
+    getsid = 147, 1;
+    fdatasync = 148, 1;
+    _sysctl = 149, 1;
+    mlock = 150, 2;
+    munlock = 151, 2;
+    mlockall = 152, 1;
+    munlockall = 153, 0;
+    sched_setparam = 154, 2;
+    sched_getparam = 155, 2;
+    sched_setscheduler = 156, 3;
+    sched_getscheduler = 157, 1;
+    sched_yield = 158, 0;
+    sched_get_priority_max = 159, 1;
+    sched_get_priority_min = 160, 1;
+    sched_rr_get_interval = 161, 2;
+    nanosleep = 162, 2;
+    mremap = 163, 5;
+    setresuid = 164, 3;
+    getresuid = 165, 3;
+    vm86 = 166, 2;
+    query_module = 167, 0;
+    poll = 168, 3;
+    nfsservctl = 169, 0;
+    setresgid = 170, 3;
+    getresgid = 171, 3;
+    prctl = 172, 5;
+    rt_sigreturn = 173, 0;
+    rt_sigaction = 174, 4;
+    rt_sigprocmask = 175, 4;
+    rt_sigpending = 176, 2;
+    rt_sigtimedwait = 177, 4;
+    rt_sigqueueinfo = 178, 3;
+    rt_sigsuspend = 179, 2;
+    pread64 = 180, 5;
+    pwrite64 = 181, 5;
+    chown = 182, 3;
+    getcwd = 183, 2;
+    capget = 184, 2;
+    capset = 185, 2;
+    sigaltstack = 186, 2;
+    sendfile = 187, 4;
+    getpmsg = 188, 0;
+    putpmsg = 189, 0;
+    vfork = 190, 0;
+    ugetrlimit = 191, 2;
+    mmap2 = 192, 6;
+    truncate64 = 193, 3;
+    ftruncate64 = 194, 3;
+    stat64 = 195, 2;
+    lstat64 = 196, 2;
+    fstat64 = 197, 2;
+    lchown32 = 198, 3;
+    getuid32 = 199, 0;
+    getgid32 = 200, 0;
+    geteuid32 = 201, 0;
+    getegid32 = 202, 0;
+    setreuid32 = 203, 2;
+    setregid32 = 204, 2;
+    getgroups32 = 205, 2;
+    setgroups32 = 206, 2;
+    fchown32 = 207, 3;
+    setresuid32 = 208, 3;
+    getresuid32 = 209, 3;
+    setresgid32 = 210, 3;
+    getresgid32 = 211, 3;
+    chown32 = 212, 3;
+    setuid32 = 213, 1;
+    setgid32 = 214, 1;
+    setfsuid32 = 215, 1;
+    setfsgid32 = 216, 1;
+    pivot_root = 217, 2;
+    mincore = 218, 3;
+    madvise = 219, 3;
+    getdents64 = 220, 3;
+    fcntl64 = 221, 3;
+    gettid = 224, 0;
+    readahead = 225, 4;
+    setxattr = 226, 5;
+    lsetxattr = 227, 5;
+    fsetxattr = 228, 5;
+    getxattr = 229, 4;
+    lgetxattr = 230, 4;
+    fgetxattr = 231, 4;
+    listxattr = 232, 3;
+    llistxattr = 233, 3;
+    flistxattr = 234, 3;
+    removexattr = 235, 2;
+    lremovexattr = 236, 2;
+    fremovexattr = 237, 2;
+    tkill = 238, 2;
+    sendfile64 = 239, 4;
+    futex = 240, 6;
+    sched_setaffinity = 241, 3;
+    sched_getaffinity = 242, 3;
+    set_thread_area = 243, 1;
+    get_thread_area = 244, 1;
+    io_setup = 245, 2;
+    io_destroy = 246, 1;
+    io_getevents = 247, 5;
+    io_submit = 248, 3;
+    io_cancel = 249, 3;
+    fadvise64 = 250, 5;
+    exit_group = 252, 1;
+    lookup_dcookie = 253, 4;
+    epoll_create = 254, 1;
+    epoll_ctl = 255, 4;
+    epoll_wait = 256, 4;
+    remap_file_pages = 257, 5;
+    set_tid_address = 258, 1;
+    timer_create = 259, 3;
+    timer_settime = 260, 4;
+    timer_gettime = 261, 2;
+    timer_getoverrun = 262, 1;
+    timer_delete = 263, 1;
+    clock_settime = 264, 2;
+    clock_gettime = 265, 2;
+    clock_getres = 266, 2;
+    clock_nanosleep = 267, 4;
+    statfs64 = 268, 3;
+    fstatfs64 = 269, 3;
+    tgkill = 270, 3;
+    utimes = 271, 2;
+    fadvise64_64 = 272, 6;
+    vserver = 273, 0;
+    mbind = 274, 6;
+    get_mempolicy = 275, 5;
+    set_mempolicy = 276, 3;
+    mq_open = 277, 4;
+    mq_unlink = 278, 1;
+    mq_timedsend = 279, 5;
+    mq_timedreceive = 280, 5;
+    mq_notify = 281, 2;
+    mq_getsetattr = 282, 3;
+    kexec_load = 283, 4;
+    waitid = 284, 5;
+    add_key = 286, 5;
+    request_key = 287, 4;
+    keyctl = 288, 5;
+    ioprio_set = 289, 3;
+    ioprio_get = 290, 2;
+    inotify_init = 291, 0;
+    inotify_add_watch = 292, 3;
+    inotify_rm_watch = 293, 2;
+    migrate_pages = 294, 4;
+    openat = 295, 4;
+    mkdirat = 296, 3;
+    mknodat = 297, 4;
+    fchownat = 298, 5;
+    futimesat = 299, 3;
+    fstatat64 = 300, 4;
+    unlinkat = 301, 3;
+    renameat = 302, 4;
+    linkat = 303, 5;
+    symlinkat = 304, 3;
+    readlinkat = 305, 4;
+    fchmodat = 306, 3;
+    faccessat = 307, 3;
+    pselect6 = 308, 6;
+    ppoll = 309, 5;
+    unshare = 310, 1;
+    set_robust_list = 311, 2;
+    get_robust_list = 312, 3;
+    splice = 313, 6;
+    sync_file_range = 314, 6;
+    tee = 315, 4;
+    vmsplice = 316, 4;
+    move_pages = 317, 6;
+    getcpu = 318, 3;
+    epoll_pwait = 319, 6;
+    utimensat = 320, 4;
+    signalfd = 321, 3;
+    timerfd_create = 322, 2;
+    eventfd = 323, 1;
+    fallocate = 324, 6;
+    timerfd_settime = 325, 4;
+    timerfd_gettime = 326, 2;
+    signalfd4 = 327, 4;
+    eventfd2 = 328, 2;
+    epoll_create1 = 329, 1;
+    dup3 = 330, 3;
+    pipe2 = 331, 2;
+    inotify_init1 = 332, 1;
+    preadv = 333, 5;
+    pwritev = 334, 5;
+    rt_tgsigqueueinfo = 335, 4;
+    perf_event_open = 336, 5;
+    recvmmsg = 337, 5;
+    fanotify_init = 338, 2;
+    fanotify_mark = 339, 6;
+    prlimit64 = 340, 4;
+    name_to_handle_at = 341, 5;
+    open_by_handle_at = 342, 3;
+    clock_adjtime = 343, 2;
+    syncfs = 344, 1;
+    sendmmsg = 345, 4;
+    setns = 346, 2;
+    process_vm_readv = 347, 6;
+    process_vm_writev = 348, 6;
+    kcmp = 349, 5;
+    finit_module = 350, 3;
+    sched_setattr = 351, 3;
+    sched_getattr = 352, 4;
+    renameat2 = 353, 5;
+    seccomp = 354, 3;
+    getrandom = 355, 3;
+    memfd_create = 356, 2;
+    bpf = 357, 3;
+    execveat = 358, 5;
+    socket = 359, 3;
+    socketpair = 360, 4;
+    bind = 361, 3;
+    connect = 362, 3;
+    listen = 363, 2;
+    accept4 = 364, 4;
+    getsockopt = 365, 5;
+    setsockopt = 366, 5;
+    getsockname = 367, 3;
+    getpeername = 368, 3;
+    sendto = 369, 6;
+    sendmsg = 370, 3;
+    recvfrom = 371, 6;
+    recvmsg = 372, 3;
+    shutdown = 373, 2;
+    userfaultfd = 374, 1;
+    membarrier = 375, 3;
+    mlock2 = 376, 3;
+    copy_file_range = 377, 6;
+    preadv2 = 378, 6;
+    pwritev2 = 379, 6;
+    pkey_mprotect = 380, 4;
+    pkey_alloc = 381, 2;
+    pkey_free = 382, 1;
+    statx = 383, 5;
+    arch_prctl = 384, 2;
+    io_pgetevents = 385, 6;
+    rseq = 386, 4;
+    semget = 393, 3;
+    semctl = 394, 4;
+    shmget = 395, 3;
+    shmctl = 396, 3;
+    shmat = 397, 3;
+    shmdt = 398, 1;
+    msgget = 399, 2;
+    msgsnd = 400, 4;
+    msgrcv = 401, 5;
+    msgctl = 402, 3;
+    clock_gettime64 = 403, 2;
+    clock_settime64 = 404, 2;
+    clock_adjtime64 = 405, 2;
+    clock_getres_time64 = 406, 2;
+    clock_nanosleep_time64 = 407, 4;
+    timer_gettime64 = 408, 2;
+    timer_settime64 = 409, 4;
+    timerfd_gettime64 = 410, 2;
+    timerfd_settime64 = 411, 4;
+    utimensat_time64 = 412, 4;
+    pselect6_time64 = 413, 6;
+    ppoll_time64 = 414, 5;
+    io_pgetevents_time64 = 416, 6;
+    recvmmsg_time64 = 417, 5;
+    mq_timedsend_time64 = 418, 5;
+    mq_timedreceive_time64 = 419, 5;
+    semtimedop_time64 = 420, 4;
+    rt_sigtimedwait_time64 = 421, 4;
+    futex_time64 = 422, 6;
+    sched_rr_get_interval_time64 = 423, 2;
+    pidfd_send_signal = 424, 4;
+    io_uring_setup = 425, 2;
+    io_uring_enter = 426, 6;
+    io_uring_register = 427, 4;
+    open_tree = 428, 3;
+    move_mount = 429, 5;
+    fsopen = 430, 2;
+    fsconfig = 431, 5;
+    fsmount = 432, 3;
+    fspick = 433, 3;
+    pidfd_open = 434, 2;
+    clone3 = 435, 2;
+    close_range = 436, 3;
+    openat2 = 437, 4;
+    pidfd_getfd = 438, 3;
+    faccessat2 = 439, 4;
+    process_madvise = 440, 5;
+    epoll_pwait2 = 441, 6;
+    mount_setattr = 442, 5;
+    quotactl_fd = 443, 4;
+    landlock_create_ruleset = 444, 3;
+    landlock_add_rule = 445, 4;
+    landlock_restrict_self = 446, 2;
+    memfd_secret = 447, 1;
+    process_mrelease = 448, 2;
+    futex_waitv = 449, 5;
+    set_mempolicy_home_node = 450, 4;
+}
@@ -0,0 +1,324 @@
+//! Decoding of ptrace-stops, and a helper to trace whole processes.
+
+use super::{cont, getevent, getsiginfo, listen, seize, syscall, Options};
+use crate::errno::Errno;
+use crate::proc;
+use crate::sys::signal::Signal;
+use crate::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+use crate::unistd::Pid;
+use crate::Result;
+use libc::c_int;
+use std::collections::HashSet;
+
+/// A ptrace-stop, decoded from a [`WaitStatus`] together with the data the
+/// kernel keeps for it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum TraceEvent {
+    /// The tracee called `fork`.  Reported with `PTRACE_O_TRACEFORK`.
+    Fork {
+        /// The new, already traced, child.
+        child: Pid,
+    },
+    /// The tracee called `vfork`.  Reported with `PTRACE_O_TRACEVFORK`.
+    Vfork {
+        /// The new, already traced, child.
+        child: Pid,
+    },
+    /// The tracee called `clone`.  Reported with `PTRACE_O_TRACECLONE`.
+    Clone {
+        /// The new, already traced, child or thread.
+        child: Pid,
+    },
+    /// A `vfork` child of the tracee exec'd or exited.  Reported with
+    /// `PTRACE_O_TRACEVFORKDONE`.
+    VforkDone {
+        /// The `vfork` child.
+        child: Pid,
+    },
+    /// The tracee called `execve`.  Reported with `PTRACE_O_TRACEEXEC`.
+    Exec {
+        /// The thread that called `execve`.  If it was not the thread group
+        /// leader, it has taken over the leader's thread ID, and no longer
+        /// exists under this one.
+        former_tid: Pid,
+    },
+    /// The tracee is about to exit.  Reported with `PTRACE_O_TRACEEXIT`.
+    Exit {
+        /// The exit status, in the form `waitpid` returns it.
+        status: c_int,
+    },
+    /// A seccomp filter returned `SECCOMP_RET_TRACE`.  Reported with
+    /// `PTRACE_O_TRACESECCOMP`.
+    Seccomp {
+        /// The `SECCOMP_RET_DATA` part of the filter's return value.
+        data: u16,
+    },
+    /// The tracee was stopped by a signal.
+    Stop {
+        /// The signal.
+        signal: Signal,
+        /// Whether this is a group-stop, in which the whole process is
+        /// stopping, rather than a signal-delivery-stop, in which `signal`
+        /// is about to be delivered.
+        ///
+        /// A group-stop should be resumed without a signal, or with
+        /// [`listen`] for tracees attached with [`seize`].
+        group_stop: bool,
+    },
+    /// The tracee stopped because of [`interrupt`](super::interrupt), or
+    /// because it was just attached to with [`seize`].
+    Interrupt,
+    /// The tracee stopped at a system call entry or exit.  Reported with
+    /// `PTRACE_O_TRACESYSGOOD` when resumed with [`syscall`].
+    Syscall,
+}
+
+impl TraceEvent {
+    /// Decode a [`WaitStatus`] into a ptrace-stop, fetching event messages
+    /// with [`getevent`] and telling group-stops apart from
+    /// signal-delivery-stops with [`getsiginfo`].
+    ///
+    /// Returns `None` for statuses that are not ptrace-stops, such as the
+    /// tracee exiting.
+    pub fn from_wait_status(status: WaitStatus) -> Result<Option<Self>> {
+        let event = match status {
+            WaitStatus::PtraceSyscall(_) => TraceEvent::Syscall,
+            WaitStatus::PtraceEvent(pid, signal, event) => {
+                Self::from_event(pid, signal, event)?
+            }
+            WaitStatus::Stopped(pid, signal) => {
+                // Without PTRACE_SEIZE, group-stops look just like
+                // signal-delivery-stops, except for having no siginfo.
+                let group_stop = match getsiginfo(pid) {
+                    Ok(_) => false,
+                    Err(Errno::EINVAL) => true,
+                    Err(e) => return Err(e),
+                };
+                TraceEvent::Stop { signal, group_stop }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(event))
+    }
+
+    fn from_event(pid: Pid, signal: Signal, event: c_int) -> Result<Self> {
+        let child = || getevent(pid).map(|msg| Pid::from_raw(msg as _));
+        Ok(match event {
+            libc::PTRACE_EVENT_FORK => TraceEvent::Fork { child: child()? },
+            libc::PTRACE_EVENT_VFORK => TraceEvent::Vfork { child: child()? },
+            libc::PTRACE_EVENT_CLONE => TraceEvent::Clone { child: child()? },
+            libc::PTRACE_EVENT_VFORK_DONE => {
+                TraceEvent::VforkDone { child: child()? }
+            }
+            libc::PTRACE_EVENT_EXEC => TraceEvent::Exec {
+                former_tid: child()?,
+            },
+            libc::PTRACE_EVENT_EXIT => TraceEvent::Exit {
+                status: getevent(pid)? as c_int,
+            },
+            libc::PTRACE_EVENT_SECCOMP => TraceEvent::Seccomp {
+                data: getevent(pid)? as u16,
+            },
+            // With PTRACE_SEIZE, group-stops are reported with the stopping
+            // signal, and every other PTRACE_EVENT_STOP with SIGTRAP.
+            libc::PTRACE_EVENT_STOP => match signal {
+                Signal::SIGSTOP
+                | Signal::SIGTSTP
+                | Signal::SIGTTIN
+                | Signal::SIGTTOU => TraceEvent::Stop {
+                    signal,
+                    group_stop: true,
+                },
+                _ => TraceEvent::Interrupt,
+            },
+            _ => return Err(Errno::EINVAL),
+        })
+    }
+}
+
+/// The result of [`Tracer::wait`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TraceStatus {
+    /// A tracee is in a ptrace-stop, and waits to be resumed with
+    /// [`Tracer::resume`].
+    Stopped(Pid, TraceEvent),
+    /// A tracee exited with the given exit code.  It is no longer traced.
+    Exited(Pid, i32),
+    /// A tracee was killed by a signal, and dumped core if the flag is set.
+    /// It is no longer traced.
+    Signaled(Pid, Signal, bool),
+}
+
+/// Traces every thread of one or more processes, attached with
+/// `PTRACE_SEIZE`.
+///
+/// The tracer keeps track of which threads it traces: threads and children
+/// the tracees create are traced from their start, and tracees leave the set
+/// when they exit.  Group-stops are resumed with `PTRACE_LISTEN`, so that
+/// job control keeps working for the traced processes.
+///
+/// Only one `Tracer` should be used per thread, as [`Tracer::wait`] waits
+/// for any child.
+///
+/// # Example
+///
+/// ```no_run
+/// # use nix::sys::ptrace::{Options, TraceEvent, TraceStatus, Tracer};
+/// # use nix::unistd::Pid;
+/// # fn f(pid: Pid) -> nix::Result<()> {
+/// let mut tracer = Tracer::new(Options::PTRACE_O_TRACEFORK);
+/// tracer.seize(pid)?;
+/// while tracer.tracees().next().is_some() {
+///     match tracer.wait()? {
+///         TraceStatus::Stopped(tid, TraceEvent::Fork { child }) => {
+///             println!("{} forked {}", tid, child);
+///             tracer.resume(tid, TraceEvent::Fork { child })?;
+///         }
+///         TraceStatus::Stopped(tid, event) => tracer.resume(tid, event)?,
+///         _ => (),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Tracer {
+    options: Options,
+    tracees: HashSet<Pid>,
+    trace_syscalls: bool,
+}
+
+impl Tracer {
+    /// Create a tracer that sets `options` on every tracee.
+    ///
+    /// `PTRACE_O_TRACECLONE` is always added, so that new threads are traced.
+    pub fn new(options: Options) -> Self {
+        Tracer {
+            options: options | Options::PTRACE_O_TRACECLONE,
+            tracees: HashSet::new(),
+            trace_syscalls: false,
+        }
+    }
+
+    /// Choose whether [`Tracer::resume`] stops tracees at the next system
+    /// call, as with [`syscall`], rather than letting them run on, as with
+    /// [`cont`].
+    ///
+    /// For [`TraceEvent::Syscall`] stops to be reported this way,
+    /// `PTRACE_O_TRACESYSGOOD` has to be among the tracer's options.
+    pub fn trace_syscalls(&mut self, enable: bool) {
+        self.trace_syscalls = enable;
+    }
+
+    /// Attach to every thread of the process `pid`, without stopping it.
+    pub fn seize(&mut self, pid: Pid) -> Result<()> {
+        seize(pid, self.options)?;
+        self.tracees.insert(pid);
+        // Threads created from now on are traced through
+        // PTRACE_O_TRACECLONE.  Repeat until no thread created before that
+        // is left.
+        loop {
+            let mut found = false;
+            for tid in proc::tasks(pid)? {
+                let tid = tid?;
+                if self.tracees.contains(&tid) {
+                    continue;
+                }
+                match seize(tid, self.options) {
+                    Ok(()) => {
+                        self.tracees.insert(tid);
+                        found = true;
+                    }
+                    // Exited, or was auto-attached in the meantime
+                    Err(Errno::ESRCH) | Err(Errno::EPERM) => (),
+                    Err(e) => return Err(e),
+                }
+            }
+            if !found {
+                return Ok(());
+            }
+        }
+    }
+
+    /// The threads currently traced.
+    pub fn tracees(&self) -> impl Iterator<Item = Pid> + '_ {
+        self.tracees.iter().copied()
+    }
+
+    /// Wait for any tracee to stop or exit.
+    ///
+    /// Returns `ECHILD` if there is nothing left to wait for.
+    pub fn wait(&mut self) -> Result<TraceStatus> {
+        loop {
+            let status = waitpid(None, Some(WaitPidFlag::__WALL))?;
+            match status {
+                WaitStatus::Exited(pid, code) => {
+                    self.tracees.remove(&pid);
+                    return Ok(TraceStatus::Exited(pid, code));
+                }
+                WaitStatus::Signaled(pid, signal, core) => {
+                    self.tracees.remove(&pid);
+                    return Ok(TraceStatus::Signaled(pid, signal, core));
+                }
+                _ => (),
+            }
+            let pid = match status.pid() {
+                Some(pid) => pid,
+                None => continue,
+            };
+            let event = match TraceEvent::from_wait_status(status) {
+                Ok(Some(event)) => event,
+                Ok(None) => continue,
+                // Killed while we looked; its exit will be reported.
+                Err(Errno::ESRCH) => continue,
+                Err(e) => return Err(e),
+            };
+            // Auto-attached children may report their first stop before
+            // their parent reports creating them.
+            self.tracees.insert(pid);
+            match event {
+                TraceEvent::Fork { child }
+                | TraceEvent::Vfork { child }
+                | TraceEvent::Clone { child } => {
+                    self.tracees.insert(child);
+                }
+                TraceEvent::Exec { former_tid } if former_tid != pid => {
+                    self.tracees.remove(&former_tid);
+                }
+                _ => (),
+            }
+            return Ok(TraceStatus::Stopped(pid, event));
+        }
+    }
+
+    /// Resume a tracee from the ptrace-stop `event`, which [`Tracer::wait`]
+    /// reported.
+    ///
+    /// Signals of signal-delivery-stops are delivered, and group-stops are
+    /// resumed with [`listen`].
+    pub fn resume(&self, pid: Pid, event: TraceEvent) -> Result<()> {
+        match event {
+            TraceEvent::Stop {
+                group_stop: true, ..
+            } => listen(pid),
+            TraceEvent::Stop { signal, .. } => self.restart(pid, Some(signal)),
+            _ => self.restart(pid, None),
+        }
+    }
+
+    /// Stop tracing `pid`, which has to be in a ptrace-stop.
+    pub fn detach(&mut self, pid: Pid) -> Result<()> {
+        super::detach(pid, None)?;
+        self.tracees.remove(&pid);
+        Ok(())
+    }
+
+    fn restart(&self, pid: Pid, signal: Option<Signal>) -> Result<()> {
+        if self.trace_syscalls {
+            syscall(pid, signal)
+        } else {
+            cont(pid, signal)
+        }
+    }
+}
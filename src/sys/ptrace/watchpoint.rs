@@ -0,0 +1,363 @@
+//! Hardware breakpoints and watchpoints of a tracee.
+//!
+//! They are set through the debug registers on x86 and x86_64, and through
+//! the `NT_ARM_HW_WATCH` and `NT_ARM_HW_BREAK` register sets on aarch64.
+//!
+//! A triggered watchpoint stops the tracee with `SIGTRAP`.  Watchpoints that
+//! are not [`WatchKind::Execute`] trigger after the access has happened.
+//!
+//! The number of slots depends on the CPU.  x86 has four, shared by every
+//! kind of watchpoint.  aarch64 has separate slots for breakpoints and for
+//! watchpoints, see [`slots`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use nix::sys::ptrace::watchpoint::{self, Slot, WatchKind};
+//! # use nix::unistd::Pid;
+//! # fn f(pid: Pid, addr: usize) -> nix::Result<()> {
+//! let slot = watchpoint::set(pid, 0, addr, 4, WatchKind::Write)?;
+//! assert_eq!(slot, Slot::Watchpoint(0));
+//! // ... resume the tracee and wait for it to stop with SIGTRAP ...
+//! if watchpoint::hit_slot(pid)? == Some(slot) {
+//!     println!("{:#x} was written to", addr);
+//! }
+//! watchpoint::clear(pid, slot)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::errno::Errno;
+use crate::unistd::Pid;
+use crate::Result;
+
+/// What accesses trigger a watchpoint.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum WatchKind {
+    /// Executing the instruction at the address, which makes the watchpoint
+    /// a hardware breakpoint.
+    Execute,
+    /// Writing to the address.
+    Write,
+    /// Reading from or writing to the address.
+    ReadWrite,
+}
+
+/// A hardware slot holding a breakpoint or a watchpoint, with its index
+/// among the slots of its kind.
+///
+/// On x86 both kinds share the same four slots, so `Breakpoint(n)` and
+/// `Watchpoint(n)` are the same debug register.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Slot {
+    /// A slot set with [`WatchKind::Execute`].
+    Breakpoint(usize),
+    /// A slot set with [`WatchKind::Write`] or [`WatchKind::ReadWrite`].
+    Watchpoint(usize),
+}
+
+impl Slot {
+    fn new(index: usize, kind: WatchKind) -> Self {
+        match kind {
+            WatchKind::Execute => Slot::Breakpoint(index),
+            _ => Slot::Watchpoint(index),
+        }
+    }
+
+    /// The index of the slot among the slots of its kind.
+    pub fn index(self) -> usize {
+        match self {
+            Slot::Breakpoint(index) | Slot::Watchpoint(index) => index,
+        }
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod arch {
+    use super::{Slot, WatchKind};
+    use crate::errno::Errno;
+    use crate::sys::ptrace::{read_user, write_user};
+    use crate::unistd::Pid;
+    use crate::Result;
+    use libc::c_void;
+    use std::mem;
+
+    pub(super) const SLOTS: usize = 4;
+
+    /// The offset of debug register `n` in `struct user`.
+    fn debugreg(n: usize) -> *mut c_void {
+        let user = mem::MaybeUninit::<libc::user>::uninit();
+        let base = user.as_ptr();
+        // No reference to the uninitialized memory is created.
+        let reg = unsafe { std::ptr::addr_of!((*base).u_debugreg[n]) };
+        (reg as usize - base as usize) as *mut c_void
+    }
+
+    fn read_debugreg(pid: Pid, n: usize) -> Result<usize> {
+        read_user(pid, debugreg(n)).map(|r| r as usize)
+    }
+
+    fn write_debugreg(pid: Pid, n: usize, value: usize) -> Result<()> {
+        // The kernel validates the debug registers.
+        unsafe { write_user(pid, debugreg(n), value as *mut c_void) }
+    }
+
+    pub(super) fn slots(_pid: Pid, _kind: WatchKind) -> Result<usize> {
+        Ok(SLOTS)
+    }
+
+    pub(super) fn set(
+        pid: Pid,
+        slot: usize,
+        addr: usize,
+        len: usize,
+        kind: WatchKind,
+    ) -> Result<()> {
+        let rw = match kind {
+            WatchKind::Execute => 0b00,
+            WatchKind::Write => 0b01,
+            WatchKind::ReadWrite => 0b11,
+        };
+        let len_bits = match (kind, len) {
+            (WatchKind::Execute, 1) => 0b00,
+            (WatchKind::Execute, _) => return Err(Errno::EINVAL),
+            (_, 1) => 0b00,
+            (_, 2) => 0b01,
+            (_, 4) => 0b11,
+            #[cfg(target_arch = "x86_64")]
+            (_, 8) => 0b10,
+            _ => return Err(Errno::EINVAL),
+        };
+        if addr % len != 0 {
+            return Err(Errno::EINVAL);
+        }
+
+        // Disable the slot while its address changes.
+        clear(pid, Slot::new(slot, kind))?;
+        write_debugreg(pid, slot, addr)?;
+        let mut dr7 = read_debugreg(pid, 7)?;
+        dr7 |= (rw | len_bits << 2) << (16 + 4 * slot);
+        // Local enable
+        dr7 |= 1 << (2 * slot);
+        write_debugreg(pid, 7, dr7)
+    }
+
+    pub(super) fn clear(pid: Pid, slot: Slot) -> Result<()> {
+        let slot = slot.index();
+        let mut dr7 = read_debugreg(pid, 7)?;
+        dr7 &= !(0b11 << (2 * slot));
+        dr7 &= !(0b1111 << (16 + 4 * slot));
+        write_debugreg(pid, 7, dr7)?;
+        write_debugreg(pid, slot, 0)
+    }
+
+    pub(super) fn hit_slot(pid: Pid) -> Result<Option<Slot>> {
+        let dr6 = read_debugreg(pid, 6)?;
+        let slot = match (0..SLOTS).find(|slot| dr6 & (1 << slot) != 0) {
+            Some(slot) => slot,
+            None => return Ok(None),
+        };
+        // DR6 is sticky
+        write_debugreg(pid, 6, 0)?;
+        let dr7 = read_debugreg(pid, 7)?;
+        if (dr7 >> (16 + 4 * slot)) & 0b11 == 0 {
+            Ok(Some(Slot::Breakpoint(slot)))
+        } else {
+            Ok(Some(Slot::Watchpoint(slot)))
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod arch {
+    use super::{Slot, WatchKind};
+    use crate::errno::Errno;
+    use crate::sys::ptrace::{
+        getregset_bytes, getsiginfo, setregset_bytes, NoteType,
+    };
+    use crate::unistd::Pid;
+    use crate::Result;
+    use std::mem;
+
+    pub(super) const SLOTS: usize = 16;
+
+    /// `si_code` of a `SIGTRAP` caused by a hardware breakpoint or
+    /// watchpoint.
+    const TRAP_HWBKPT: libc::c_int = 4;
+
+    /// `struct user_hwdebug_state`
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct HwDebugState {
+        dbg_info: u32,
+        pad: u32,
+        dbg_regs: [HwDebugReg; SLOTS],
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct HwDebugReg {
+        addr: u64,
+        ctrl: u32,
+        pad: u32,
+    }
+
+    fn note(kind: WatchKind) -> NoteType {
+        match kind {
+            WatchKind::Execute => NoteType::NT_ARM_HW_BREAK,
+            _ => NoteType::NT_ARM_HW_WATCH,
+        }
+    }
+
+    fn as_bytes(state: &mut HwDebugState) -> &mut [u8] {
+        unsafe {
+            std::slice::from_raw_parts_mut(
+                state as *mut HwDebugState as *mut u8,
+                mem::size_of::<HwDebugState>(),
+            )
+        }
+    }
+
+    fn read_state(pid: Pid, note: NoteType) -> Result<HwDebugState> {
+        let mut state = HwDebugState {
+            dbg_info: 0,
+            pad: 0,
+            dbg_regs: [HwDebugReg {
+                addr: 0,
+                ctrl: 0,
+                pad: 0,
+            }; SLOTS],
+        };
+        getregset_bytes(pid, note, as_bytes(&mut state))?;
+        Ok(state)
+    }
+
+    fn write_state(
+        pid: Pid,
+        note: NoteType,
+        mut state: HwDebugState,
+    ) -> Result<()> {
+        setregset_bytes(pid, note, as_bytes(&mut state))
+    }
+
+    pub(super) fn slots(pid: Pid, kind: WatchKind) -> Result<usize> {
+        read_state(pid, note(kind)).map(|s| (s.dbg_info & 0xff) as usize)
+    }
+
+    pub(super) fn set(
+        pid: Pid,
+        slot: usize,
+        addr: usize,
+        len: usize,
+        kind: WatchKind,
+    ) -> Result<()> {
+        // Load/store control
+        let lsc = match kind {
+            WatchKind::Execute => 0b00,
+            WatchKind::Write => 0b10,
+            WatchKind::ReadWrite => 0b11,
+        };
+        // Byte address select, relative to the aligned double word for
+        // watchpoints and to the instruction for breakpoints.
+        let (aligned, bas) = match kind {
+            WatchKind::Execute if len == 4 && addr % 4 == 0 => (addr, 0xf),
+            WatchKind::Execute => return Err(Errno::EINVAL),
+            _ if len == 0 || addr % 8 + len > 8 => return Err(Errno::EINVAL),
+            _ => (addr & !7, ((1u32 << len) - 1) << (addr % 8)),
+        };
+        let note = note(kind);
+        let mut state = read_state(pid, note)?;
+        if slot >= (state.dbg_info & 0xff) as usize {
+            return Err(Errno::EINVAL);
+        }
+        // Enabled, for EL0 only
+        let ctrl = 1 | 0b10 << 1 | lsc << 3 | bas << 5;
+        state.dbg_regs[slot] = HwDebugReg {
+            addr: aligned as u64,
+            ctrl,
+            pad: 0,
+        };
+        write_state(pid, note, state)
+    }
+
+    pub(super) fn clear(pid: Pid, slot: Slot) -> Result<()> {
+        let (note, slot) = match slot {
+            Slot::Breakpoint(slot) => (NoteType::NT_ARM_HW_BREAK, slot),
+            Slot::Watchpoint(slot) => (NoteType::NT_ARM_HW_WATCH, slot),
+        };
+        let mut state = read_state(pid, note)?;
+        if slot >= (state.dbg_info & 0xff) as usize {
+            return Err(Errno::EINVAL);
+        }
+        state.dbg_regs[slot].addr = 0;
+        state.dbg_regs[slot].ctrl = 0;
+        write_state(pid, note, state)
+    }
+
+    pub(super) fn hit_slot(pid: Pid) -> Result<Option<Slot>> {
+        let info = getsiginfo(pid)?;
+        if info.si_signo != libc::SIGTRAP || info.si_code != TRAP_HWBKPT {
+            return Ok(None);
+        }
+        let addr = unsafe { info.si_addr() } as u64;
+        let kinds: [(NoteType, fn(usize) -> Slot); 2] = [
+            (NoteType::NT_ARM_HW_WATCH, Slot::Watchpoint),
+            (NoteType::NT_ARM_HW_BREAK, Slot::Breakpoint),
+        ];
+        for (note, slot) in kinds {
+            let state = read_state(pid, note)?;
+            let count = (state.dbg_info & 0xff) as usize;
+            let hit = state.dbg_regs[..count.min(SLOTS)].iter().position(|r| {
+                r.ctrl & 1 != 0 && (r.addr..r.addr + 8).contains(&addr)
+            });
+            if let Some(hit) = hit {
+                return Ok(Some(slot(hit)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// The number of slots available for watchpoints of the given kind.
+pub fn slots(pid: Pid, kind: WatchKind) -> Result<usize> {
+    arch::slots(pid, kind)
+}
+
+/// Watch `len` bytes at `addr` in the tracee, using the slot with index
+/// `slot` among those for `kind`, and return that slot.  Any watchpoint
+/// already in the slot is replaced.
+///
+/// On x86 the range must be naturally aligned and 1, 2, 4 or, on x86_64
+/// only, 8 bytes long.  On aarch64 it must lie within one aligned double
+/// word.  [`WatchKind::Execute`] needs a `len` of 1 on x86, and of 4 on
+/// aarch64.  Returns `EINVAL` otherwise.
+pub fn set(
+    pid: Pid,
+    slot: usize,
+    addr: usize,
+    len: usize,
+    kind: WatchKind,
+) -> Result<Slot> {
+    if slot >= arch::SLOTS || len == 0 {
+        return Err(Errno::EINVAL);
+    }
+    arch::set(pid, slot, addr, len, kind)?;
+    Ok(Slot::new(slot, kind))
+}
+
+/// Disable the breakpoint or watchpoint in `slot`.
+pub fn clear(pid: Pid, slot: Slot) -> Result<()> {
+    if slot.index() >= arch::SLOTS {
+        return Err(Errno::EINVAL);
+    }
+    arch::clear(pid, slot)
+}
+
+/// Find out which breakpoint or watchpoint stopped the tracee, if any.
+///
+/// On x86 this decodes, and then resets, the debug status register DR6.
+/// On aarch64 it matches the address of the `SIGTRAP` against the slots,
+/// looking at watchpoints before breakpoints.
+pub fn hit_slot(pid: Pid) -> Result<Option<Slot>> {
+    arch::hit_slot(pid)
+}
@@ -0,0 +1,360 @@
+//! Safe signal handling by forwarding signals to a pipe.
+//!
+//! Installing a handler with [`sigaction`] requires the handler to be
+//! async-signal-safe, which is easy to get wrong.  A [`SignalPipe`] instead
+//! installs a small handler owned by Nix that only writes the number of the
+//! received signal to a pipe (the "self-pipe trick").  The read end of that
+//! pipe can then be handled from ordinary code, for example from an event loop
+//! built on `poll`, `epoll` or `kqueue`.  This works on every platform,
+//! including those without [`signalfd`](https://man7.org/linux/man-pages/man2/signalfd.2.html).
+//!
+//! Several `SignalPipe`s may subscribe to the same signal; each of them
+//! receives every delivery.  The signal action that was in place before the
+//! first subscriber registered is restored when the last subscriber is
+//! dropped.
+//!
+//! # Signal coalescing
+//!
+//! As with any other signal handling mechanism, signals of the same type that
+//! arrive while one is already pending may be merged.  Additionally, if a
+//! subscriber does not drain its pipe and the pipe fills up, further
+//! notifications for that subscriber are discarded.
+//!
+//! [`sigaction`]: crate::sys::signal::sigaction
+use crate::errno::{self, Errno};
+use crate::sys::signal::{
+    sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal,
+};
+use crate::Result;
+use std::convert::TryFrom;
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
+use std::sync::atomic::{AtomicI32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+libc_bitflags! {
+    /// Options for the read end of a [`SignalPipe`].
+    pub struct SigPipeFlags: libc::c_int {
+        /// Do not block when reading from an empty pipe.
+        O_NONBLOCK;
+        /// Close the read end of the pipe on `execve`.
+        O_CLOEXEC;
+    }
+}
+
+/// Maximum number of `SignalPipe`s that may exist at the same time.
+const MAX_SUBSCRIBERS: usize = 64;
+/// Every signal number representable by [`Signal`] is below this value.
+const MAX_SIGNAL: usize = 64;
+
+/// A subscriber as seen from the signal handler.
+struct Slot {
+    /// Write end of the subscriber's pipe, or `-1` if the slot is free.
+    fd: AtomicI32,
+    /// Bit `n` is set if the subscriber wants to be notified of signal `n`.
+    mask: AtomicU64,
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const EMPTY_SLOT: Slot = Slot {
+    fd: AtomicI32::new(-1),
+    mask: AtomicU64::new(0),
+};
+
+static SLOTS: [Slot; MAX_SUBSCRIBERS] = [EMPTY_SLOT; MAX_SUBSCRIBERS];
+/// Number of signal handler invocations currently reading `SLOTS`.
+static ACTIVE_HANDLERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Bookkeeping that is only touched outside of signal handlers.
+struct Registry {
+    /// Whether each slot in `SLOTS` is in use.
+    used: [bool; MAX_SUBSCRIBERS],
+    /// Number of subscribers for each signal.
+    subscribers: [usize; MAX_SIGNAL],
+    /// Action that was installed before the first subscriber registered.
+    previous: [Option<SigAction>; MAX_SIGNAL],
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    used: [false; MAX_SUBSCRIBERS],
+    subscribers: [0; MAX_SIGNAL],
+    previous: [None; MAX_SIGNAL],
+});
+
+extern "C" fn forward_signal(signum: libc::c_int) {
+    let saved_errno = errno::errno();
+    ACTIVE_HANDLERS.fetch_add(1, Ordering::SeqCst);
+    let bit = 1u64 << signum;
+    let byte = signum as u8;
+    for slot in SLOTS.iter() {
+        if slot.mask.load(Ordering::SeqCst) & bit != 0 {
+            let fd = slot.fd.load(Ordering::SeqCst);
+            if fd >= 0 {
+                // A full pipe means the subscriber has not yet consumed
+                // earlier notifications, so it is fine to drop this one.
+                unsafe {
+                    libc::write(
+                        fd,
+                        &byte as *const u8 as *const libc::c_void,
+                        1,
+                    )
+                };
+            }
+        }
+    }
+    ACTIVE_HANDLERS.fetch_sub(1, Ordering::SeqCst);
+    errno::set_errno(saved_errno);
+}
+
+/// Creates a pipe whose ends are both non-blocking and close-on-exec.
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "emscripten",
+    target_os = "freebsd",
+    target_os = "illumos",
+    target_os = "linux",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "redox",
+    target_os = "solaris"
+))]
+fn pipe() -> Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [-1; 2];
+    let res = unsafe {
+        libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC | libc::O_NONBLOCK)
+    };
+    Errno::result(res)?;
+    unsafe { Ok((OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1]))) }
+}
+
+/// Creates a pipe whose ends are both non-blocking and close-on-exec.
+///
+/// Without `pipe2`, the flags can only be set after the pipe was created, so
+/// a concurrent `fork` and `exec` may leak the pipe into the child.
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "emscripten",
+    target_os = "freebsd",
+    target_os = "illumos",
+    target_os = "linux",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "redox",
+    target_os = "solaris"
+)))]
+fn pipe() -> Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [-1; 2];
+    let res = unsafe { libc::pipe(fds.as_mut_ptr()) };
+    Errno::result(res)?;
+    let fds =
+        unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+    for fd in [&fds.0, &fds.1] {
+        set_nonblock(fd.as_raw_fd(), true)?;
+        set_cloexec(fd.as_raw_fd(), true)?;
+    }
+    Ok(fds)
+}
+
+fn set_nonblock(fd: RawFd, on: bool) -> Result<()> {
+    let res = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    let fl = Errno::result(res)?;
+    let fl = if on {
+        fl | libc::O_NONBLOCK
+    } else {
+        fl & !libc::O_NONBLOCK
+    };
+    let res = unsafe { libc::fcntl(fd, libc::F_SETFL, fl) };
+    Errno::result(res).map(drop)
+}
+
+fn set_cloexec(fd: RawFd, on: bool) -> Result<()> {
+    let fd_flags = if on { libc::FD_CLOEXEC } else { 0 };
+    let res = unsafe { libc::fcntl(fd, libc::F_SETFD, fd_flags) };
+    Errno::result(res).map(drop)
+}
+
+/// Receives signals through a pipe written to by a handler owned by Nix.
+///
+/// See the [module level documentation](self) for details.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::signal::{raise, SigSet, Signal};
+/// # use nix::sys::signalpipe::*;
+/// let mut mask = SigSet::empty();
+/// mask.add(Signal::SIGUSR2);
+/// let mut pipe = SignalPipe::with_flags(&mask, SigPipeFlags::O_NONBLOCK).unwrap();
+///
+/// raise(Signal::SIGUSR2).unwrap();
+/// assert_eq!(pipe.read_signal().unwrap(), Some(Signal::SIGUSR2));
+/// assert_eq!(pipe.read_signal().unwrap(), None);
+/// ```
+#[derive(Debug)]
+pub struct SignalPipe {
+    read: OwnedFd,
+    // Kept alive until the slot has been released in `drop`.
+    _write: OwnedFd,
+    slot: usize,
+    mask: SigSet,
+}
+
+impl SignalPipe {
+    /// Subscribes to the signals in `mask`, with blocking reads.
+    pub fn new(mask: &SigSet) -> Result<SignalPipe> {
+        Self::with_flags(mask, SigPipeFlags::empty())
+    }
+
+    /// Subscribes to the signals in `mask`.
+    ///
+    /// The first subscription to a signal replaces its current action with a
+    /// handler that forwards it to every subscribed `SignalPipe`.
+    ///
+    /// # Errors
+    ///
+    /// * `EINVAL` if `mask` contains a signal that can't be caught, such as
+    ///   `SIGKILL` or `SIGSTOP`.
+    /// * `ENOSPC` if too many `SignalPipe`s exist already.
+    pub fn with_flags(
+        mask: &SigSet,
+        flags: SigPipeFlags,
+    ) -> Result<SignalPipe> {
+        // The write end must never block the signal handler, and is private
+        // to this process.
+        let (read, write) = pipe()?;
+        if !flags.contains(SigPipeFlags::O_NONBLOCK) {
+            set_nonblock(read.as_raw_fd(), false)?;
+        }
+        if !flags.contains(SigPipeFlags::O_CLOEXEC) {
+            set_cloexec(read.as_raw_fd(), false)?;
+        }
+
+        let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+        let slot = registry
+            .used
+            .iter()
+            .position(|used| !used)
+            .ok_or(Errno::ENOSPC)?;
+
+        let mut installed = Vec::new();
+        for signal in mask {
+            let signum = signal as usize;
+            if registry.subscribers[signum] == 0 {
+                let action = SigAction::new(
+                    SigHandler::Handler(forward_signal),
+                    SaFlags::SA_RESTART,
+                    SigSet::all(),
+                );
+                // Safe because `forward_signal` is async-signal-safe.
+                match unsafe { sigaction(signal, &action) } {
+                    Ok(previous) => registry.previous[signum] = Some(previous),
+                    Err(e) => {
+                        for signal in installed {
+                            restore(&mut registry, signal);
+                        }
+                        return Err(e);
+                    }
+                }
+            }
+            registry.subscribers[signum] += 1;
+            installed.push(signal);
+        }
+
+        let bits = mask.iter().fold(0u64, |bits, s| bits | 1 << s as usize);
+        registry.used[slot] = true;
+        SLOTS[slot].fd.store(write.as_raw_fd(), Ordering::SeqCst);
+        SLOTS[slot].mask.store(bits, Ordering::SeqCst);
+
+        Ok(SignalPipe {
+            read,
+            _write: write,
+            slot,
+            mask: *mask,
+        })
+    }
+
+    /// Returns the set of signals this pipe is subscribed to.
+    pub fn mask(&self) -> SigSet {
+        self.mask
+    }
+
+    /// Reads the next signal from the pipe.
+    ///
+    /// Returns `Ok(None)` if the pipe was created with
+    /// [`SigPipeFlags::O_NONBLOCK`] and no signal is pending.
+    ///
+    /// # Errors
+    ///
+    /// * `EPIPE` if the pipe reached end-of-file, which only happens if its
+    ///   write end was closed behind our back.
+    /// * `EINVAL` if the pipe carried something that is not a signal number.
+    pub fn read_signal(&mut self) -> Result<Option<Signal>> {
+        let mut byte = 0u8;
+        let res = Errno::result(unsafe {
+            libc::read(
+                self.read.as_raw_fd(),
+                &mut byte as *mut u8 as *mut libc::c_void,
+                1,
+            )
+        });
+        match res {
+            Ok(1) => Signal::try_from(libc::c_int::from(byte)).map(Some),
+            Ok(_) => Err(Errno::EPIPE),
+            Err(Errno::EAGAIN) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+/// Drops one subscriber of `signal`, restoring the previous action if it was
+/// the last one.
+fn restore(registry: &mut Registry, signal: Signal) {
+    let signum = signal as usize;
+    registry.subscribers[signum] -= 1;
+    if registry.subscribers[signum] == 0 {
+        if let Some(previous) = registry.previous[signum].take() {
+            // Safe because `previous` was returned by `sigaction` itself.
+            let _ = unsafe { sigaction(signal, &previous) };
+        }
+    }
+}
+
+impl Drop for SignalPipe {
+    fn drop(&mut self) {
+        let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+        SLOTS[self.slot].mask.store(0, Ordering::SeqCst);
+        SLOTS[self.slot].fd.store(-1, Ordering::SeqCst);
+        // A handler that started before the stores above may still be about
+        // to write to our pipe, so wait for it before the fd can be closed.
+        while ACTIVE_HANDLERS.load(Ordering::SeqCst) != 0 {
+            std::hint::spin_loop();
+        }
+        registry.used[self.slot] = false;
+        for signal in &self.mask {
+            restore(&mut registry, signal);
+        }
+    }
+}
+
+impl AsFd for SignalPipe {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.read.as_fd()
+    }
+}
+
+impl AsRawFd for SignalPipe {
+    fn as_raw_fd(&self) -> RawFd {
+        self.read.as_raw_fd()
+    }
+}
+
+/// Yields received signals, ending once no signal is pending on a
+/// non-blocking pipe.  Errors are yielded as they occur.
+impl Iterator for SignalPipe {
+    type Item = Result<Signal>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_signal().transpose()
+    }
+}
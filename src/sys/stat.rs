@@ -9,12 +9,17 @@ pub use libc::c_ulong;
 pub use libc::stat as FileStat;
 pub use libc::{dev_t, mode_t};
 
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+use libc::c_int;
+
 #[cfg(not(target_os = "redox"))]
 use crate::fcntl::{at_rawfd, AtFlags};
 use crate::sys::time::{TimeSpec, TimeVal};
 use crate::{errno::Errno, NixPath, Result};
 use std::mem;
 use std::os::unix::io::RawFd;
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+use std::os::unix::io::{AsFd, AsRawFd};
 
 libc_bitflags!(
     /// "File type" flags for `mknod` and related functions.
@@ -478,3 +483,237 @@ pub fn mkdirat<P: ?Sized + NixPath>(
 
     Errno::result(res).map(drop)
 }
+
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+mod ffi {
+    #![allow(non_camel_case_types)]
+
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct fsxattr {
+        pub fsx_xflags: u32,
+        pub fsx_extsize: u32,
+        pub fsx_nextents: u32,
+        pub fsx_projid: u32,
+        pub fsx_cowextsize: u32,
+        pub fsx_pad: [u8; 8],
+    }
+
+    // The request codes are defined with a `long`, but the kernel reads
+    // and writes an `int`.
+    pub const FS_IOC_GETFLAGS: u8 = 1;
+    pub const FS_IOC_SETFLAGS: u8 = 2;
+
+    ioctl_read!(fs_ioc_fsgetxattr, b'X', 31, fsxattr);
+    ioctl_write_ptr!(fs_ioc_fssetxattr, b'X', 32, fsxattr);
+}
+
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+bitflags::bitflags! {
+    /// Attributes of an inode, as shown by `lsattr`.  Some are specific to
+    /// some file systems, and changing others needs `CAP_LINUX_IMMUTABLE`.
+    ///
+    /// See also [`ioctl_iflags(2)`].
+    ///
+    /// [`ioctl_iflags(2)`]: https://man7.org/linux/man-pages/man2/ioctl_iflags.2.html
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "ioctl"))))]
+    pub struct InodeFlags: c_int {
+        /// Overwrite the data when the file is deleted.  Not implemented.
+        const FS_SECRM_FL = 0x0000_0001;
+        /// Keep the data when the file is deleted.  Not implemented.
+        const FS_UNRM_FL = 0x0000_0002;
+        /// Compress the file.
+        const FS_COMPR_FL = 0x0000_0004;
+        /// Write changes synchronously.
+        const FS_SYNC_FL = 0x0000_0008;
+        /// The file cannot be modified, deleted, renamed or linked to.
+        const FS_IMMUTABLE_FL = 0x0000_0010;
+        /// The file can only be appended to.
+        const FS_APPEND_FL = 0x0000_0020;
+        /// Skip the file when backing up with `dump`.
+        const FS_NODUMP_FL = 0x0000_0040;
+        /// Do not update the access time of the file.
+        const FS_NOATIME_FL = 0x0000_0080;
+        /// Do not compress the file.
+        const FS_NOCOMP_FL = 0x0000_0400;
+        /// The file is encrypted.  Cannot be changed.
+        const FS_ENCRYPT_FL = 0x0000_0800;
+        /// The directory is indexed by a hash tree.  Cannot be changed.
+        const FS_INDEX_FL = 0x0000_1000;
+        /// Journal the data of the file, and not only its metadata.
+        const FS_JOURNAL_DATA_FL = 0x0000_4000;
+        /// Do not merge the tail of the file with that of others.
+        const FS_NOTAIL_FL = 0x0000_8000;
+        /// Write changes to the directory synchronously.
+        const FS_DIRSYNC_FL = 0x0001_0000;
+        /// The directory is the top of a hierarchy, for block allocation.
+        const FS_TOPDIR_FL = 0x0002_0000;
+        /// The file is stored in extents.  Cannot be changed.
+        const FS_EXTENT_FL = 0x0008_0000;
+        /// The file has fs-verity enabled.  Cannot be changed.
+        const FS_VERITY_FL = 0x0010_0000;
+        /// Do not copy on write the file.
+        const FS_NOCOW_FL = 0x0080_0000;
+        /// Access the file directly, bypassing the page cache.
+        const FS_DAX_FL = 0x0200_0000;
+        /// The data of the file is stored in its inode.  Cannot be changed.
+        const FS_INLINE_DATA_FL = 0x1000_0000;
+        /// Files created in the directory inherit its project id.
+        const FS_PROJINHERIT_FL = 0x2000_0000;
+        /// Look names up in the directory ignoring their case.  Only for
+        /// empty directories.
+        const FS_CASEFOLD_FL = 0x4000_0000;
+    }
+}
+
+/// Get the attributes of the inode of `fd`.
+///
+/// Returns `ENOTTY` if the file system does not support them.
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "ioctl"))))]
+#[doc(alias("FS_IOC_GETFLAGS", "lsattr"))]
+pub fn get_inode_flags<Fd: AsFd>(fd: Fd) -> Result<InodeFlags> {
+    let request = request_code_read!(
+        b'f',
+        ffi::FS_IOC_GETFLAGS,
+        mem::size_of::<libc::c_long>()
+    );
+    let mut flags: c_int = 0;
+    let res = unsafe {
+        libc::ioctl(
+            fd.as_fd().as_raw_fd(),
+            request as crate::sys::ioctl::ioctl_num_type,
+            &mut flags,
+        )
+    };
+    Errno::result(res)?;
+    // Keep unknown attributes, so that setting them back does not clear them.
+    Ok(unsafe { InodeFlags::from_bits_unchecked(flags) })
+}
+
+/// Set the attributes of the inode of `fd`.
+///
+/// Attributes which cannot be changed are ignored.
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "ioctl"))))]
+#[doc(alias("FS_IOC_SETFLAGS", "chattr"))]
+pub fn set_inode_flags<Fd: AsFd>(fd: Fd, flags: InodeFlags) -> Result<()> {
+    let request = request_code_write!(
+        b'f',
+        ffi::FS_IOC_SETFLAGS,
+        mem::size_of::<libc::c_long>()
+    );
+    let flags = flags.bits();
+    let res = unsafe {
+        libc::ioctl(
+            fd.as_fd().as_raw_fd(),
+            request as crate::sys::ioctl::ioctl_num_type,
+            &flags,
+        )
+    };
+    Errno::result(res).map(drop)
+}
+
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+bitflags::bitflags! {
+    /// Attributes of an inode in an [`FsXattr`].  The ones shared with
+    /// [`InodeFlags`] are the same attributes.
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "ioctl"))))]
+    pub struct FsXFlags: u32 {
+        /// The data is on the realtime device.
+        const FS_XFLAG_REALTIME = 0x0000_0001;
+        /// The file has preallocated space.
+        const FS_XFLAG_PREALLOC = 0x0000_0002;
+        /// The file cannot be modified, deleted, renamed or linked to.
+        const FS_XFLAG_IMMUTABLE = 0x0000_0008;
+        /// The file can only be appended to.
+        const FS_XFLAG_APPEND = 0x0000_0010;
+        /// Write changes synchronously.
+        const FS_XFLAG_SYNC = 0x0000_0020;
+        /// Do not update the access time of the file.
+        const FS_XFLAG_NOATIME = 0x0000_0040;
+        /// Skip the file when backing up with `dump`.
+        const FS_XFLAG_NODUMP = 0x0000_0080;
+        /// Files created in the directory have `FS_XFLAG_REALTIME` set.
+        const FS_XFLAG_RTINHERIT = 0x0000_0100;
+        /// Files created in the directory inherit its project id.
+        const FS_XFLAG_PROJINHERIT = 0x0000_0200;
+        /// Symbolic links cannot be created in the directory.
+        const FS_XFLAG_NOSYMLINKS = 0x0000_0400;
+        /// Allocate space in multiples of the extent size hint.
+        const FS_XFLAG_EXTSIZE = 0x0000_0800;
+        /// Files created in the directory inherit its extent size hint.
+        const FS_XFLAG_EXTSZINHERIT = 0x0000_1000;
+        /// Do not defragment the file.
+        const FS_XFLAG_NODEFRAG = 0x0000_2000;
+        /// Allocate the files of the directory together.
+        const FS_XFLAG_FILESTREAM = 0x0000_4000;
+        /// Access the file directly, bypassing the page cache.
+        const FS_XFLAG_DAX = 0x0000_8000;
+        /// Use the copy on write extent size hint.
+        const FS_XFLAG_COWEXTSIZE = 0x0001_0000;
+        /// The file has extended attributes.  Cannot be changed.
+        const FS_XFLAG_HASATTR = 0x8000_0000;
+    }
+}
+
+/// Extended attributes of an inode, as in `struct fsxattr`.
+///
+/// Get them with [`get_fsxattr`], change them, and set them back with
+/// [`set_fsxattr`].
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "ioctl"))))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct FsXattr {
+    /// The attributes.
+    pub xflags: FsXFlags,
+    /// The extent size hint, in bytes, used with `FS_XFLAG_EXTSIZE`.
+    pub extsize: u32,
+    /// The number of extents of the file.  Cannot be changed.
+    pub nextents: u32,
+    /// The project id, which quotas can be set on.
+    pub projid: u32,
+    /// The copy on write extent size hint, in bytes, used with
+    /// `FS_XFLAG_COWEXTSIZE`.
+    pub cowextsize: u32,
+}
+
+/// Get the extended attributes of the inode of `fd`.
+///
+/// Returns `ENOTTY` if the file system does not support them.
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "ioctl"))))]
+#[doc(alias("FS_IOC_FSGETXATTR"))]
+pub fn get_fsxattr<Fd: AsFd>(fd: Fd) -> Result<FsXattr> {
+    let mut raw = ffi::fsxattr::default();
+    unsafe { ffi::fs_ioc_fsgetxattr(fd.as_fd().as_raw_fd(), &mut raw) }?;
+    Ok(FsXattr {
+        // Keep unknown attributes, like in `get_inode_flags`.
+        xflags: unsafe { FsXFlags::from_bits_unchecked(raw.fsx_xflags) },
+        extsize: raw.fsx_extsize,
+        nextents: raw.fsx_nextents,
+        projid: raw.fsx_projid,
+        cowextsize: raw.fsx_cowextsize,
+    })
+}
+
+/// Set the extended attributes of the inode of `fd`.
+///
+/// Like other attributes, the project id can only be changed by the owner of
+/// the file or with `CAP_FOWNER`.  Outside of the initial user namespace,
+/// changing it always fails with `EINVAL`.
+#[cfg(all(target_os = "linux", feature = "ioctl"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "fs", feature = "ioctl"))))]
+#[doc(alias("FS_IOC_FSSETXATTR"))]
+pub fn set_fsxattr<Fd: AsFd>(fd: Fd, attr: &FsXattr) -> Result<()> {
+    let raw = ffi::fsxattr {
+        fsx_xflags: attr.xflags.bits(),
+        fsx_extsize: attr.extsize,
+        fsx_nextents: attr.nextents,
+        fsx_projid: attr.projid,
+        fsx_cowextsize: attr.cowextsize,
+        fsx_pad: [0; 8],
+    };
+    unsafe { ffi::fs_ioc_fssetxattr(fd.as_fd().as_raw_fd(), &raw) }.map(drop)
+}
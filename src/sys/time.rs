@@ -93,6 +93,12 @@ pub(crate) mod timer {
         /// Flags that are used for arming the timer.
         pub struct TimerSetTimeFlags: libc::c_int {
             const TFD_TIMER_ABSTIME = libc::TFD_TIMER_ABSTIME;
+            /// Together with `TFD_TIMER_ABSTIME` on a `CLOCK_REALTIME` or
+            /// `CLOCK_REALTIME_ALARM` timerfd, report a discontinuous change
+            /// to the clock as [`TimerEvent::ClockChanged`].
+            ///
+            /// [`TimerEvent::ClockChanged`]: crate::sys::timerfd::TimerEvent::ClockChanged
+            const TFD_TIMER_CANCEL_ON_SET = libc::TFD_TIMER_CANCEL_ON_SET;
         }
     }
     #[cfg(any(
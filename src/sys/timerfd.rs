@@ -28,8 +28,31 @@
 //! // We wait for the timer to expire.
 //! timer.wait().unwrap();
 //! ```
+//!
+//! Wake up at an absolute point in time, and notice if the wall clock is
+//! changed in the meantime.
+//! ```
+//! # use nix::sys::timerfd::{TimerFd, ClockId, TimerFlags, TimerSetTimeFlags,
+//! #    TimerEvent};
+//! # use nix::sys::time::{TimeSpec, TimeValLike};
+//! # use nix::time::{clock_gettime, ClockId as Clock};
+//! let timer = TimerFd::new(ClockId::CLOCK_REALTIME, TimerFlags::empty())
+//!     .unwrap();
+//!
+//! let now = clock_gettime(Clock::CLOCK_REALTIME).unwrap();
+//! timer.set_at(
+//!     now + TimeSpec::milliseconds(10),
+//!     TimerSetTimeFlags::TFD_TIMER_CANCEL_ON_SET,
+//! ).unwrap();
+//!
+//! match timer.wait().unwrap() {
+//!     TimerEvent::Expired(n) => println!("expired {} time(s)", n),
+//!     TimerEvent::ClockChanged => println!("the clock was set"),
+//! }
+//! ```
 use crate::sys::time::timer::TimerSpec;
 pub use crate::sys::time::timer::{Expiration, TimerSetTimeFlags};
+use crate::sys::time::TimeSpec;
 use crate::unistd::read;
 use crate::{errno::Errno, Result};
 use libc::c_int;
@@ -75,12 +98,29 @@ libc_enum! {
         /// that the system was suspended.
         CLOCK_BOOTTIME,
         /// Like `CLOCK_REALTIME`, but will wake the system if it is suspended.
+        ///
+        /// Requires the `CAP_WAKE_ALARM` capability.
         CLOCK_REALTIME_ALARM,
         /// Like `CLOCK_BOOTTIME`, but will wake the system if it is suspended.
+        ///
+        /// Requires the `CAP_WAKE_ALARM` capability.
         CLOCK_BOOTTIME_ALARM,
     }
 }
 
+/// The outcome of reading a [`TimerFd`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TimerEvent {
+    /// The timer expired this many times since it was last read or set.
+    ///
+    /// A count greater than one means that expirations were missed.
+    Expired(u64),
+    /// The clock was changed discontinuously, so an absolute timer set with
+    /// [`TimerSetTimeFlags::TFD_TIMER_CANCEL_ON_SET`] was cancelled.  It has to
+    /// be set again.
+    ClockChanged,
+}
+
 libc_bitflags! {
     /// Additional flags to change the behaviour of the file descriptor at the
     /// time of creation.
@@ -197,16 +237,67 @@ impl TimerFd {
         .map(drop)
     }
 
+    /// Sets a one shot alarm at the absolute point in time `deadline`, as
+    /// measured by the clock the timer was created with.
+    ///
+    /// This is equivalent to [`TimerFd::set`] with [`Expiration::OneShot`] and
+    /// `TFD_TIMER_ABSTIME`, which is added to `flags`.  Pass
+    /// `TFD_TIMER_CANCEL_ON_SET` to be told when the clock is changed, see
+    /// [`TimerEvent::ClockChanged`].
+    #[doc(alias("timerfd_settime"))]
+    pub fn set_at(
+        &self,
+        deadline: TimeSpec,
+        flags: TimerSetTimeFlags,
+    ) -> Result<()> {
+        self.set(
+            Expiration::OneShot(deadline),
+            flags | TimerSetTimeFlags::TFD_TIMER_ABSTIME,
+        )
+    }
+
     /// Wait for the configured alarm to expire.
     ///
+    /// Returns the number of expirations since the timer was last read or
+    /// set, or [`TimerEvent::ClockChanged`] if the timer was cancelled because
+    /// the clock changed.
+    ///
     /// Note: If the alarm is unset, then you will wait forever.
-    pub fn wait(&self) -> Result<()> {
-        while let Err(e) = read(self.fd.as_fd().as_raw_fd(), &mut [0u8; 8]) {
-            if e != Errno::EINTR {
-                return Err(e);
+    pub fn wait(&self) -> Result<TimerEvent> {
+        let mut buf = [0u8; 8];
+        loop {
+            match read(self.fd.as_fd().as_raw_fd(), &mut buf) {
+                Ok(_) => {
+                    return Ok(TimerEvent::Expired(u64::from_ne_bytes(buf)))
+                }
+                Err(Errno::EINTR) => continue,
+                Err(Errno::ECANCELED) => return Ok(TimerEvent::ClockChanged),
+                Err(e) => return Err(e),
             }
         }
+    }
 
-        Ok(())
+    /// Like [`TimerFd::wait`], but returns `None` immediately instead of
+    /// blocking if the alarm has not expired yet.
+    ///
+    /// The timer is polled before it is read, so this does not block as long
+    /// as no other thread reads from it in between.  If the `TimerFd` is
+    /// shared with threads that also wait on it, create it with
+    /// [`TimerFlags::TFD_NONBLOCK`]; otherwise this may block until the next
+    /// expiration when another thread consumes the current one first.
+    pub fn try_wait(&self) -> Result<Option<TimerEvent>> {
+        let mut pollfd = libc::pollfd {
+            fd: self.fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        if Errno::result(unsafe { libc::poll(&mut pollfd, 1, 0) })? == 0 {
+            return Ok(None);
+        }
+        match self.wait() {
+            Ok(event) => Ok(Some(event)),
+            Err(Errno::EAGAIN) => Ok(None),
+            Err(e) => Err(e),
+        }
     }
 }
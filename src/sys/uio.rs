@@ -91,6 +91,86 @@ pub fn preadv<Fd: AsFd>(
     Errno::result(res).map(|r| r as usize)
 }
 
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+bitflags::bitflags! {
+    /// Flags for [`preadv2`] and [`pwritev2`].
+    pub struct RwfFlags: c_int {
+        /// Poll for completion of high priority I/O, instead of waiting for
+        /// an interrupt.  Only for files opened with `O_DIRECT`.
+        const RWF_HIPRI = libc::RWF_HIPRI;
+        /// Like `O_DSYNC`, for this write.
+        const RWF_DSYNC = libc::RWF_DSYNC;
+        /// Like `O_SYNC`, for this write.
+        const RWF_SYNC = libc::RWF_SYNC;
+        /// Fail with `EAGAIN` instead of blocking, for instance when the
+        /// data is not in the page cache.  Only for reads before Linux 4.14.
+        const RWF_NOWAIT = libc::RWF_NOWAIT;
+        /// Like `O_APPEND`, for this write.  The offset is ignored.
+        const RWF_APPEND = libc::RWF_APPEND;
+        /// Write at the offset even if the file was opened with `O_APPEND`.
+        /// Requires Linux 6.9.
+        const RWF_NOAPPEND = 0x0000_0020;
+    }
+}
+
+/// Write to `fd` from buffers in `iov`, at `offset`, or at and advancing the
+/// file offset if it is `None`.
+///
+/// Like [`pwritev`], but with `flags` changing the behaviour of this write
+/// only.  Requires Linux 4.6.
+///
+/// See also [pwritev2(2)](https://man7.org/linux/man-pages/man2/pwritev2.2.html)
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+pub fn pwritev2<Fd: AsFd>(
+    fd: Fd,
+    iov: &[IoSlice<'_>],
+    offset: Option<off_t>,
+    flags: RwfFlags,
+) -> Result<usize> {
+    // SAFETY: same as in writev()
+    let res = unsafe {
+        libc::pwritev2(
+            fd.as_fd().as_raw_fd(),
+            iov.as_ptr() as *const libc::iovec,
+            iov.len() as c_int,
+            offset.unwrap_or(-1),
+            flags.bits(),
+        )
+    };
+
+    Errno::result(res).map(|r| r as usize)
+}
+
+/// Read from `fd` filling buffers in `iov`, at `offset`, or at and advancing
+/// the file offset if it is `None`.
+///
+/// Like [`preadv`], but with `flags` changing the behaviour of this read
+/// only.  Requires Linux 4.6.
+///
+/// See also [preadv2(2)](https://man7.org/linux/man-pages/man2/preadv2.2.html)
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
+pub fn preadv2<Fd: AsFd>(
+    fd: Fd,
+    iov: &mut [IoSliceMut<'_>],
+    offset: Option<off_t>,
+    flags: RwfFlags,
+) -> Result<usize> {
+    // SAFETY: same as in readv()
+    let res = unsafe {
+        libc::preadv2(
+            fd.as_fd().as_raw_fd(),
+            iov.as_ptr() as *const libc::iovec,
+            iov.len() as c_int,
+            offset.unwrap_or(-1),
+            flags.bits(),
+        )
+    };
+
+    Errno::result(res).map(|r| r as usize)
+}
+
 /// Low-level write to a file, with specified offset.
 ///
 /// See also [pwrite(2)](https://pubs.opengroup.org/onlinepubs/9699919799/functions/pwrite.html)
@@ -143,6 +143,31 @@ impl WaitStatus {
             PtraceEvent(p, _, _) | PtraceSyscall(p) => Some(p),
         }
     }
+
+    /// Fetches the details of the system call a tracee stopped in, with
+    /// [`ptrace::get_syscall_info`].
+    ///
+    /// Returns `None` unless this is a `PtraceSyscall` stop, or a
+    /// `PtraceEvent` stop for `PTRACE_EVENT_SECCOMP`.
+    ///
+    /// [`ptrace::get_syscall_info`]: crate::sys::ptrace::get_syscall_info
+    #[cfg(all(target_os = "linux", target_env = "gnu", feature = "ptrace"))]
+    #[cfg_attr(docsrs, doc(cfg(all())))]
+    pub fn syscall_info(
+        &self,
+    ) -> Option<Result<crate::sys::ptrace::SyscallInfo>> {
+        use crate::sys::ptrace::{get_syscall_info, Event};
+
+        match *self {
+            WaitStatus::PtraceSyscall(pid) => Some(get_syscall_info(pid)),
+            WaitStatus::PtraceEvent(pid, _, event)
+                if event == Event::PTRACE_EVENT_SECCOMP as c_int =>
+            {
+                Some(get_syscall_info(pid))
+            }
+            _ => None,
+        }
+    }
 }
 
 fn exited(status: i32) -> bool {
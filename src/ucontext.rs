@@ -1,10 +1,37 @@
-#[cfg(not(target_env = "musl"))]
 use crate::errno::Errno;
+use crate::sys::mman::{mmap, mprotect, munmap, MapFlags, ProtFlags};
 use crate::sys::signal::SigSet;
-#[cfg(not(target_env = "musl"))]
 use crate::Result;
+use libc::c_void;
 #[cfg(not(target_env = "musl"))]
 use std::mem;
+use std::num::NonZeroUsize;
+use std::os::unix::io::BorrowedFd;
+use std::ptr::NonNull;
+
+#[cfg(not(target_os = "freebsd"))]
+use libc as ffi;
+
+// The context functions are not yet in libc on FreeBSD.
+#[cfg(target_os = "freebsd")]
+mod ffi {
+    use libc::{c_int, ucontext_t};
+
+    extern "C" {
+        pub fn getcontext(ucp: *mut ucontext_t) -> c_int;
+        pub fn setcontext(ucp: *const ucontext_t) -> c_int;
+        pub fn makecontext(
+            ucp: *mut ucontext_t,
+            func: extern "C" fn(),
+            argc: c_int,
+            ...
+        );
+        pub fn swapcontext(
+            oucp: *mut ucontext_t,
+            ucp: *const ucontext_t,
+        ) -> c_int;
+    }
+}
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct UContext {
@@ -15,7 +42,7 @@ impl UContext {
     #[cfg(not(target_env = "musl"))]
     pub fn get() -> Result<UContext> {
         let mut context = mem::MaybeUninit::<libc::ucontext_t>::uninit();
-        let res = unsafe { libc::getcontext(context.as_mut_ptr()) };
+        let res = unsafe { ffi::getcontext(context.as_mut_ptr()) };
         Errno::result(res).map(|_| unsafe {
             UContext {
                 context: context.assume_init(),
@@ -26,11 +53,69 @@ impl UContext {
     #[cfg(not(target_env = "musl"))]
     pub fn set(&self) -> Result<()> {
         let res = unsafe {
-            libc::setcontext(&self.context as *const libc::ucontext_t)
+            ffi::setcontext(&self.context as *const libc::ucontext_t)
         };
         Errno::result(res).map(drop)
     }
 
+    /// Turns this context into one that calls `func` on `stack` once it is
+    /// activated by [`set`](Self::set) or [`swap`](Self::swap).
+    ///
+    /// When `func` returns, `link` is activated.  If `link` is `None`, the
+    /// calling thread exits instead.
+    ///
+    /// See also [makecontext(3)](https://man7.org/linux/man-pages/man3/makecontext.3.html).
+    ///
+    /// # Safety
+    ///
+    /// * `stack` must outlive every activation of this context and of its
+    ///   copies, and only one of them may run on it at a time.
+    /// * `link`, if given, must be valid whenever `func` returns.
+    /// * Some architectures store pointers into the context itself, so
+    ///   neither this context nor `link` may be moved once they have been
+    ///   initialized.  Keep them at a stable address, for example in a `Box`.
+    #[cfg(not(target_env = "musl"))]
+    pub unsafe fn make(
+        &mut self,
+        stack: &Stack,
+        link: Option<&UContext>,
+        func: extern "C" fn(),
+    ) -> Result<()> {
+        Errno::result(ffi::getcontext(&mut self.context))?;
+        self.context.uc_stack.ss_sp = stack.as_ptr();
+        self.context.uc_stack.ss_size = stack.size();
+        self.context.uc_stack.ss_flags = 0;
+        self.context.uc_link = link.map_or(std::ptr::null_mut(), |l| {
+            &l.context as *const libc::ucontext_t as *mut libc::ucontext_t
+        });
+        ffi::makecontext(&mut self.context, func, 0);
+        Ok(())
+    }
+
+    /// Saves the current context in `from` and activates `to`.
+    ///
+    /// This returns once `from` is activated again.
+    ///
+    /// See also [swapcontext(3)](https://man7.org/linux/man-pages/man3/swapcontext.3.html).
+    ///
+    /// # Safety
+    ///
+    /// * `to` must have been initialized by [`get`](Self::get),
+    ///   [`make`](Self::make) or `swap`, and not moved since.
+    /// * If `to` was made by [`make`](Self::make), or saved while running on
+    ///   such a context, the invariants of `make` must still hold: its stack
+    ///   and link are still valid, and no other context is running on that
+    ///   stack.  Since `UContext` is `Copy`, this includes copies of `to`.
+    /// * `from` must not be moved while it may be activated again.
+    #[cfg(not(target_env = "musl"))]
+    pub unsafe fn swap(from: &mut UContext, to: &UContext) -> Result<()> {
+        let res = ffi::swapcontext(
+            &mut from.context,
+            &to.context as *const libc::ucontext_t,
+        );
+        Errno::result(res).map(drop)
+    }
+
     pub fn sigmask_mut(&mut self) -> &mut SigSet {
         unsafe {
             &mut *(&mut self.context.uc_sigmask as *mut libc::sigset_t
@@ -45,3 +130,68 @@ impl UContext {
         }
     }
 }
+
+/// An owned stack for contexts created by [`UContext::make`].
+///
+/// The stack is allocated with [`mmap`] and preceded by an inaccessible guard
+/// page, so that overflowing it raises `SIGSEGV` instead of corrupting
+/// adjacent memory.  It is unmapped on drop.
+#[derive(Debug)]
+pub struct Stack {
+    base: NonNull<c_void>,
+    len: usize,
+    guard: usize,
+}
+
+impl Stack {
+    /// Allocates a stack of at least `size` usable bytes.
+    ///
+    /// `size` is rounded up to a multiple of the page size.
+    pub fn new(size: usize) -> Result<Stack> {
+        let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        if size == 0 {
+            return Err(Errno::EINVAL);
+        }
+        let len =
+            size.checked_add(2 * page - 1).ok_or(Errno::ENOMEM)? & !(page - 1);
+        let base = unsafe {
+            mmap::<BorrowedFd>(
+                None,
+                NonZeroUsize::new(len).unwrap(),
+                ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+                MapFlags::MAP_PRIVATE
+                    | MapFlags::MAP_ANONYMOUS
+                    | MapFlags::MAP_STACK,
+                None,
+                0,
+            )
+        }?;
+        let stack = Stack {
+            base: NonNull::new(base).unwrap(),
+            len,
+            guard: page,
+        };
+        // Stacks grow downwards, so the guard page goes at the lowest address.
+        unsafe { mprotect(base, page, ProtFlags::PROT_NONE) }?;
+        Ok(stack)
+    }
+
+    /// Returns a pointer to the lowest usable address of the stack.
+    pub fn as_ptr(&self) -> *mut c_void {
+        unsafe {
+            (self.base.as_ptr() as *mut u8).add(self.guard) as *mut c_void
+        }
+    }
+
+    /// Returns the number of usable bytes, excluding the guard page.
+    pub fn size(&self) -> usize {
+        self.len - self.guard
+    }
+}
+
+impl Drop for Stack {
+    fn drop(&mut self) {
+        let res = unsafe { munmap(self.base.as_ptr(), self.len) };
+        debug_assert!(res.is_ok(), "munmap of a context stack failed");
+    }
+}
@@ -19,12 +19,15 @@ mod test_aio;
     target_os = "haiku"
 )))]
 mod test_ioctl;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod test_memfd;
 #[cfg(not(target_os = "redox"))]
 mod test_mman;
 #[cfg(not(target_os = "redox"))]
 mod test_select;
 #[cfg(target_os = "linux")]
 mod test_signalfd;
+mod test_signalpipe;
 #[cfg(not(any(target_os = "redox", target_os = "haiku")))]
 mod test_socket;
 #[cfg(not(any(target_os = "redox")))]
@@ -43,6 +46,17 @@ mod test_wait;
 
 #[cfg(any(target_os = "android", target_os = "linux"))]
 mod test_epoll;
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod test_eventfd;
 #[cfg(target_os = "linux")]
 mod test_inotify;
 mod test_pthread;
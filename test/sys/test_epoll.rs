@@ -24,3 +24,85 @@ pub fn test_epoll_ctl() {
     epoll_ctl(efd, EpollOp::EpollCtlAdd, 1, &mut event).unwrap();
     epoll_ctl(efd, EpollOp::EpollCtlDel, 1, None).unwrap();
 }
+
+#[test]
+pub fn test_epoll_registry() {
+    use nix::sys::epoll::EpollRegistry;
+    use nix::sys::eventfd::EventFd;
+
+    let mut registry = EpollRegistry::new(EpollCreateFlags::empty()).unwrap();
+    let first = EventFd::new().unwrap();
+    let second = EventFd::new().unwrap();
+    registry.add(&first, EpollFlags::EPOLLIN, "first").unwrap();
+    registry
+        .add(&second, EpollFlags::EPOLLIN, "second")
+        .unwrap();
+    assert_eq!(
+        registry
+            .add(&second, EpollFlags::EPOLLIN, "again")
+            .unwrap_err(),
+        Errno::EEXIST
+    );
+
+    second.arm().unwrap();
+    let mut events = [EpollEvent::empty(); 4];
+    let ready: Vec<_> = registry.wait(&mut events, 0).unwrap().collect();
+    assert_eq!(ready, [(&"second", EpollFlags::EPOLLIN)]);
+
+    assert_eq!(registry.delete(&second).unwrap(), "second");
+    assert_eq!(registry.delete(&second).unwrap_err(), Errno::ENOENT);
+    assert_eq!(registry.wait(&mut events, 0).unwrap().count(), 0);
+}
+
+#[test]
+pub fn test_epoll_oneshot_rearm() {
+    use nix::sys::epoll::EpollRegistry;
+    use nix::sys::eventfd::EventFd;
+
+    let mut registry = EpollRegistry::new(EpollCreateFlags::empty()).unwrap();
+    let efd = EventFd::new().unwrap();
+    registry
+        .add(&efd, EpollFlags::EPOLLIN | EpollFlags::EPOLLONESHOT, 7u32)
+        .unwrap();
+    efd.arm().unwrap();
+
+    let mut events = [EpollEvent::empty(); 1];
+    assert_eq!(registry.wait(&mut events, 0).unwrap().count(), 1);
+    // The eventfd is still readable, but has been disabled.
+    assert_eq!(registry.wait(&mut events, 0).unwrap().count(), 0);
+
+    registry.rearm(&efd, EpollFlags::EPOLLIN).unwrap();
+    let ready: Vec<_> = registry.wait(&mut events, 0).unwrap().collect();
+    assert_eq!(ready, [(&7, EpollFlags::EPOLLIN)]);
+}
+
+#[test]
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+pub fn test_epoll_pwait2() {
+    use crate::*;
+    use nix::sys::epoll::Epoll;
+    use nix::sys::eventfd::{eventfd, EfdFlags};
+    use nix::sys::signal::SigSet;
+    use nix::sys::time::{TimeSpec, TimeValLike};
+    use nix::unistd::write;
+    use std::os::unix::io::AsRawFd;
+
+    let epoll = Epoll::new(EpollCreateFlags::empty()).unwrap();
+    let efd = eventfd(0, EfdFlags::empty()).unwrap();
+    epoll
+        .add(&efd, EpollEvent::new(EpollFlags::EPOLLIN, 42))
+        .unwrap();
+
+    let mut events = [EpollEvent::empty()];
+    let timeout = Some(TimeSpec::milliseconds(1));
+    match epoll.pwait2(&mut events, timeout, Some(&SigSet::empty())) {
+        Err(Errno::ENOSYS) => {
+            skip!("epoll_pwait2 requires Linux 5.11 or later");
+        }
+        res => assert_eq!(res.unwrap(), 0),
+    }
+
+    write(efd.as_raw_fd(), &1u64.to_ne_bytes()).unwrap();
+    assert_eq!(epoll.pwait2(&mut events, None, None).unwrap(), 1);
+    assert_eq!(events[0].data(), 42);
+}
@@ -0,0 +1,86 @@
+use nix::errno::Errno;
+use nix::poll::{poll, PollFd, PollFlags};
+use nix::sys::eventfd::{EfdFlags, EventFd};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+fn readable(efd: &EventFd) -> bool {
+    let mut fds = [PollFd::new(efd, PollFlags::POLLIN)];
+    poll(&mut fds, 0).unwrap() == 1
+}
+
+#[test]
+fn test_eventfd_counter() {
+    let efd = EventFd::from_flags(EfdFlags::EFD_NONBLOCK).unwrap();
+    assert!(!readable(&efd));
+    assert_eq!(efd.read(), Err(Errno::EAGAIN));
+    assert_eq!(efd.try_read(), Ok(None));
+
+    efd.write(3).unwrap();
+    efd.write(4).unwrap();
+    assert!(readable(&efd));
+    assert_eq!(efd.read(), Ok(7));
+    assert!(!readable(&efd));
+
+    assert_eq!(efd.write(u64::MAX), Err(Errno::EINVAL));
+    efd.write(u64::MAX - 1).unwrap();
+    assert_eq!(efd.write(1), Err(Errno::EAGAIN));
+    assert_eq!(efd.try_read(), Ok(Some(u64::MAX - 1)));
+}
+
+#[test]
+fn test_eventfd_semaphore() {
+    let efd = EventFd::from_value_and_flags(
+        2,
+        EfdFlags::EFD_SEMAPHORE | EfdFlags::EFD_NONBLOCK,
+    )
+    .unwrap();
+    assert_eq!(efd.read(), Ok(1));
+    assert!(readable(&efd));
+    assert_eq!(efd.read(), Ok(1));
+    assert_eq!(efd.read(), Err(Errno::EAGAIN));
+}
+
+#[test]
+fn test_eventfd_arm_defuse() {
+    for flags in [EfdFlags::empty(), EfdFlags::EFD_SEMAPHORE] {
+        let efd = EventFd::from_flags(flags).unwrap();
+        assert_eq!(efd.defuse(), Ok(0));
+        efd.arm().unwrap();
+        efd.arm().unwrap();
+        assert!(readable(&efd));
+        assert_eq!(efd.defuse(), Ok(2));
+        assert!(!readable(&efd));
+        // Neither blocks on a blocking EventFd.
+        assert_eq!(efd.try_read(), Ok(None));
+        assert_eq!(efd.defuse(), Ok(0));
+        // defuse leaves the EventFd blocking.
+        #[cfg(any(
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "linux"
+        ))]
+        {
+            use nix::fcntl::{fcntl, FcntlArg, OFlag};
+            use std::os::unix::io::AsRawFd;
+
+            let flags = fcntl(efd.as_raw_fd(), FcntlArg::F_GETFL).unwrap();
+            assert!(
+                !OFlag::from_bits_truncate(flags).contains(OFlag::O_NONBLOCK)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_eventfd_blocking_read() {
+    let efd = Arc::new(EventFd::new().unwrap());
+    let waker = Arc::clone(&efd);
+    let thread = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        waker.write(5).unwrap();
+    });
+    assert_eq!(efd.read(), Ok(5));
+    thread.join().unwrap();
+}
@@ -63,3 +63,85 @@ pub fn test_inotify_multi_events() {
 
     assert_eq!(events[3].cookie, events[4].cookie);
 }
+
+#[test]
+pub fn test_inotify_read_events_into() {
+    let instance = Inotify::init(InitFlags::IN_NONBLOCK).unwrap();
+    let tempdir = tempfile::tempdir().unwrap();
+
+    instance
+        .add_watch(tempdir.path(), AddWatchFlags::IN_CREATE)
+        .unwrap();
+
+    let mut buffer = [0u8; 1024];
+    let events = instance.read_events_into(&mut buffer);
+    assert_eq!(events.unwrap_err(), Errno::EAGAIN);
+
+    File::create(tempdir.path().join("first")).unwrap();
+    File::create(tempdir.path().join("second")).unwrap();
+
+    let names: Vec<_> = instance
+        .read_events_into(&mut buffer)
+        .unwrap()
+        .map(|event| event.name.unwrap().to_owned())
+        .collect();
+    assert_eq!(names, [OsString::from("first"), OsString::from("second")]);
+
+    // A buffer that can't hold a single event is rejected by the kernel.
+    File::create(tempdir.path().join("third")).unwrap();
+    let mut small = [0u8; 4];
+    let events = instance.read_events_into(&mut small);
+    assert_eq!(events.unwrap_err(), Errno::EINVAL);
+}
+
+#[test]
+pub fn test_inotify_as_fd() {
+    use nix::poll::{poll, PollFd, PollFlags};
+
+    let instance = Inotify::init(InitFlags::IN_NONBLOCK).unwrap();
+    let tempdir = tempfile::tempdir().unwrap();
+    instance
+        .add_watch(tempdir.path(), AddWatchFlags::IN_CREATE)
+        .unwrap();
+    File::create(tempdir.path().join("test")).unwrap();
+
+    let mut fds = [PollFd::new(&instance, PollFlags::POLLIN)];
+    assert_eq!(poll(&mut fds, 1000).unwrap(), 1);
+}
+
+#[test]
+pub fn test_recursive_watcher() {
+    use nix::sys::inotify::RecursiveWatcher;
+    use std::fs::create_dir_all;
+
+    let tempdir = tempfile::tempdir().unwrap();
+    let root = tempdir.path();
+    create_dir_all(root.join("a/b")).unwrap();
+
+    let mut watcher = RecursiveWatcher::new(
+        root,
+        AddWatchFlags::IN_CREATE | AddWatchFlags::IN_MOVE,
+        InitFlags::IN_NONBLOCK,
+    )
+    .unwrap();
+    assert_eq!(watcher.watched_dirs().count(), 3);
+
+    // A directory created after the watcher started gets watched too.
+    create_dir_all(root.join("a/b/c")).unwrap();
+    let events = watcher.read_events().unwrap();
+    assert_eq!(events[0].path, root.join("a/b/c"));
+    File::create(root.join("a/b/c/file")).unwrap();
+    let events = watcher.read_events().unwrap();
+    assert_eq!(events[0].path, root.join("a/b/c/file"));
+    assert_eq!(events[0].mask, AddWatchFlags::IN_CREATE);
+
+    // Moving a directory within the tree keeps its watches.
+    rename(root.join("a/b"), root.join("d")).unwrap();
+    let events = watcher.read_events().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].cookie, events[1].cookie);
+    File::create(root.join("d/c/moved")).unwrap();
+    let events = watcher.read_events().unwrap();
+    assert_eq!(events[0].path, root.join("d/c/moved"));
+    assert_eq!(watcher.watched_dirs().count(), 4);
+}
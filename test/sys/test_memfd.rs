@@ -0,0 +1,146 @@
+use nix::errno::Errno;
+use nix::fcntl::SealFlag;
+use nix::sys::memfd::{HugePageSize, MemFdBuilder};
+use nix::sys::mman::ProtFlags;
+use nix::sys::uio::pwrite;
+use std::ffi::CStr;
+
+use crate::*;
+
+fn name() -> &'static CStr {
+    CStr::from_bytes_with_nul(b"nix-test\0").unwrap()
+}
+
+#[test]
+fn test_memfd_seals() {
+    let memfd = MemFdBuilder::new(name())
+        .allow_sealing(true)
+        .create()
+        .unwrap();
+    memfd.set_len(8192).unwrap();
+    assert_eq!(memfd.size().unwrap(), 8192);
+    assert_eq!(pwrite(&memfd, b"sealed", 4096).unwrap(), 6);
+    assert_eq!(memfd.seals().unwrap(), SealFlag::empty());
+
+    memfd
+        .add_seals(SealFlag::F_SEAL_GROW | SealFlag::F_SEAL_SHRINK)
+        .unwrap();
+    assert_eq!(memfd.set_len(16384), Err(Errno::EPERM));
+    assert_eq!(memfd.set_len(4096), Err(Errno::EPERM));
+    // The contents can still change, so the file cannot be mapped safely.
+    assert_eq!(memfd.map(ProtFlags::PROT_READ).unwrap_err(), Errno::EPERM);
+
+    memfd
+        .add_seals(SealFlag::F_SEAL_WRITE | SealFlag::F_SEAL_SEAL)
+        .unwrap();
+    assert_eq!(
+        memfd.seals().unwrap(),
+        SealFlag::F_SEAL_GROW
+            | SealFlag::F_SEAL_SHRINK
+            | SealFlag::F_SEAL_WRITE
+            | SealFlag::F_SEAL_SEAL
+    );
+    assert_eq!(pwrite(&memfd, b"sealed", 0), Err(Errno::EPERM));
+    assert_eq!(
+        memfd.add_seals(SealFlag::F_SEAL_FUTURE_WRITE),
+        Err(Errno::EPERM)
+    );
+
+    let mut region = memfd
+        .map(ProtFlags::PROT_READ | ProtFlags::PROT_WRITE)
+        .unwrap();
+    assert_eq!(region.size(), 8192);
+    assert_eq!(&region.as_slice().unwrap()[4096..4102], b"sealed");
+    // Private writes do not reach the file.
+    region.as_mut_slice().unwrap()[0] = 1;
+    let other = memfd.map(ProtFlags::PROT_READ).unwrap();
+    assert_eq!(other.as_slice().unwrap()[0], 0);
+}
+
+#[test]
+fn test_memfd_without_sealing() {
+    let memfd = MemFdBuilder::new(name()).create().unwrap();
+    assert_eq!(memfd.seals().unwrap(), SealFlag::F_SEAL_SEAL);
+    assert_eq!(memfd.add_seals(SealFlag::F_SEAL_WRITE), Err(Errno::EPERM));
+}
+
+#[test]
+fn test_memfd_future_write() {
+    let memfd = MemFdBuilder::new(name())
+        .allow_sealing(true)
+        .create()
+        .unwrap();
+    memfd.set_len(4096).unwrap();
+    memfd
+        .add_seals(SealFlag::F_SEAL_FUTURE_WRITE | SealFlag::F_SEAL_SHRINK)
+        .unwrap();
+    assert_eq!(pwrite(&memfd, b"x", 0), Err(Errno::EPERM));
+    // Existing writable shared mappings could still modify the file.
+    assert_eq!(memfd.map(ProtFlags::PROT_READ).unwrap_err(), Errno::EPERM);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_memfd_noexec_seal() {
+    let memfd = match MemFdBuilder::new(name()).executable(false).create() {
+        Err(Errno::EINVAL) => {
+            skip!("MFD_NOEXEC_SEAL requires Linux 6.3 or later");
+        }
+        res => res.unwrap(),
+    };
+    assert!(memfd.seals().unwrap().contains(SealFlag::F_SEAL_EXEC));
+}
+
+#[test]
+fn test_memfd_huge_pages() {
+    let memfd = match MemFdBuilder::new(name())
+        .huge_pages(HugePageSize::Size2MB)
+        .create()
+    {
+        Err(Errno::EINVAL | Errno::ENOENT) => {
+            skip!("2MB huge pages are not supported");
+        }
+        res => res.unwrap(),
+    };
+    // The size must be a multiple of the huge page size.
+    assert_eq!(memfd.set_len(4096), Err(Errno::EINVAL));
+    memfd.set_len(2 << 20).unwrap();
+    assert_eq!(memfd.size().unwrap(), 2 << 20);
+}
+
+#[test]
+#[cfg(all(
+    target_os = "linux",
+    any(target_env = "gnu", target_env = "musl"),
+    any(target_arch = "aarch64", target_arch = "x86_64")
+))]
+fn test_memfd_secret() {
+    use nix::sys::memfd::{memfd_secret, MemFdSecretFlag};
+    use nix::sys::mman::{MapFlags, MmapRegion};
+    use std::num::NonZeroUsize;
+
+    let secret = match memfd_secret(MemFdSecretFlag::O_CLOEXEC) {
+        Err(Errno::ENOSYS) => {
+            skip!("secret memory is not enabled");
+        }
+        res => res.unwrap(),
+    };
+    secret.set_len(4096).unwrap();
+    assert_eq!(
+        secret.add_seals(SealFlag::F_SEAL_SHRINK),
+        Err(Errno::EINVAL)
+    );
+    let mut region = unsafe {
+        MmapRegion::file(
+            &secret,
+            0,
+            NonZeroUsize::new(4096).unwrap(),
+            ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+            MapFlags::MAP_SHARED,
+        )
+    }
+    .unwrap();
+    drop(secret);
+    region.as_mut_slice().unwrap()[..6].copy_from_slice(b"hunter");
+    assert_eq!(&region.as_slice().unwrap()[..6], b"hunter");
+}
@@ -1,3 +1,5 @@
+#[cfg(target_os = "linux")]
+use crate::*;
 use nix::sys::mman::{mmap, MapFlags, ProtFlags};
 use std::{num::NonZeroUsize, os::unix::io::BorrowedFd};
 
@@ -120,3 +122,422 @@ fn test_mremap_shrink() {
     // The first KB should still be accessible and have the old data in it.
     assert_eq!(slice[ONE_K - 1], 0xFF);
 }
+
+#[test]
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn test_mincore() {
+    use nix::errno::Errno;
+    use nix::sys::mman::{mincore, munmap};
+    use nix::unistd::{sysconf, SysconfVar};
+
+    let page_size = sysconf(SysconfVar::PAGE_SIZE).unwrap().unwrap() as usize;
+    let len = 4 * page_size;
+    unsafe {
+        let ptr = mmap::<BorrowedFd>(
+            None,
+            NonZeroUsize::new(len).unwrap(),
+            ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+            MapFlags::MAP_PRIVATE | MapFlags::MAP_ANONYMOUS,
+            None,
+            0,
+        )
+        .unwrap();
+        assert_eq!(mincore(ptr, len).unwrap(), [false; 4]);
+
+        *(ptr as *mut u8).add(page_size) = 1;
+        assert_eq!(mincore(ptr, len).unwrap(), [false, true, false, false]);
+        assert_eq!(mincore(ptr, 1).unwrap(), [false]);
+        assert_eq!(mincore(ptr, usize::MAX), Err(Errno::EINVAL));
+
+        munmap(ptr, len).unwrap();
+    }
+}
+
+#[test]
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+fn test_mlock2() {
+    use nix::sys::mman::{mincore, mlock2, munlock, munmap, MlockFlags};
+    use nix::unistd::{sysconf, SysconfVar};
+
+    let page_size = sysconf(SysconfVar::PAGE_SIZE).unwrap().unwrap() as usize;
+    let len = 2 * page_size;
+    unsafe {
+        let ptr = mmap::<BorrowedFd>(
+            None,
+            NonZeroUsize::new(len).unwrap(),
+            ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+            MapFlags::MAP_PRIVATE | MapFlags::MAP_ANONYMOUS,
+            None,
+            0,
+        )
+        .unwrap();
+        match mlock2(ptr, len, MlockFlags::MLOCK_ONFAULT) {
+            Err(nix::errno::Errno::EPERM) | Err(nix::errno::Errno::ENOMEM) => {
+                munmap(ptr, len).unwrap();
+                skip!("mlock2 needs CAP_IPC_LOCK or a larger RLIMIT_MEMLOCK");
+            }
+            res => res.unwrap(),
+        }
+        // Nothing is faulted in up front.
+        assert_eq!(mincore(ptr, len).unwrap(), [false, false]);
+        munlock(ptr, len).unwrap();
+
+        mlock2(ptr, len, MlockFlags::empty()).unwrap();
+        assert_eq!(mincore(ptr, len).unwrap(), [true, true]);
+        munmap(ptr, len).unwrap();
+    }
+}
+
+/// Open a pidfd for this process.
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+fn pidfd_self() -> Option<std::os::unix::io::OwnedFd> {
+    use std::os::unix::io::FromRawFd;
+
+    let fd = unsafe {
+        nix::libc::syscall(nix::libc::SYS_pidfd_open, nix::libc::getpid(), 0)
+    };
+    (fd >= 0).then(|| unsafe {
+        std::os::unix::io::OwnedFd::from_raw_fd(fd as nix::libc::c_int)
+    })
+}
+
+#[test]
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+fn test_process_madvise() {
+    use nix::errno::Errno;
+    use nix::sys::mman::{munmap, process_madvise, ProcessAdvise};
+    use nix::unistd::{sysconf, SysconfVar};
+    use std::io::IoSlice;
+
+    let pidfd = pidfd_self();
+    if pidfd.is_none() {
+        skip!("pidfd_open requires Linux 5.3");
+    }
+    let pidfd = pidfd.unwrap();
+    let page_size = sysconf(SysconfVar::PAGE_SIZE).unwrap().unwrap() as usize;
+    let len = 2 * page_size;
+    let buf = unsafe {
+        let ptr = mmap::<BorrowedFd>(
+            None,
+            NonZeroUsize::new(len).unwrap(),
+            ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+            MapFlags::MAP_PRIVATE | MapFlags::MAP_ANONYMOUS,
+            None,
+            0,
+        )
+        .unwrap();
+        std::slice::from_raw_parts_mut(ptr as *mut u8, len)
+    };
+    buf.fill(1);
+
+    let iov = [
+        IoSlice::new(&buf[..page_size]),
+        IoSlice::new(&buf[page_size..]),
+    ];
+    match process_madvise(&pidfd, &iov, ProcessAdvise::MADV_PAGEOUT) {
+        Err(Errno::ENOSYS) => {
+            skip!("process_madvise requires Linux 5.10");
+        }
+        Err(Errno::EPERM) => {
+            skip!("process_madvise requires CAP_SYS_NICE");
+        }
+        res => assert_eq!(res, Ok(len)),
+    }
+    // The contents are kept.
+    assert!(buf.iter().all(|&b| b == 1));
+    unsafe { munmap(buf.as_mut_ptr().cast(), len) }.unwrap();
+}
+
+#[test]
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+fn test_process_mrelease() {
+    use nix::errno::Errno;
+    use nix::sys::mman::process_mrelease;
+
+    let pidfd = pidfd_self();
+    if pidfd.is_none() {
+        skip!("pidfd_open requires Linux 5.3");
+    }
+    let pidfd = pidfd.unwrap();
+    match process_mrelease(&pidfd) {
+        Err(Errno::ENOSYS) => {
+            skip!("process_mrelease requires Linux 5.15");
+        }
+        // This process is not exiting.
+        res => assert_eq!(res, Err(Errno::EINVAL)),
+    }
+}
+
+#[test]
+#[cfg(all(
+    target_os = "linux",
+    any(target_env = "gnu", target_env = "musl"),
+    any(target_arch = "aarch64", target_arch = "x86", target_arch = "x86_64")
+))]
+fn test_protection_key() {
+    use nix::errno::Errno;
+    use nix::sys::mman::{munmap, pkey_mprotect, PkeyRights, ProtectionKey};
+
+    let key = match ProtectionKey::alloc(PkeyRights::PKEY_DISABLE_WRITE) {
+        Err(Errno::ENOSPC) | Err(Errno::EINVAL) | Err(Errno::ENOSYS) => {
+            skip!("protection keys are not supported");
+        }
+        res => res.unwrap(),
+    };
+    assert!(key.as_raw() > 0);
+
+    let len = 4096;
+    let ptr = unsafe {
+        mmap::<BorrowedFd>(
+            None,
+            NonZeroUsize::new(len).unwrap(),
+            ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+            MapFlags::MAP_PRIVATE | MapFlags::MAP_ANONYMOUS,
+            None,
+            0,
+        )
+        .unwrap()
+    };
+    unsafe {
+        pkey_mprotect(
+            ptr,
+            len,
+            ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+            &key,
+        )
+    }
+    .unwrap();
+    // Reads are still allowed.
+    assert_eq!(unsafe { *(ptr as *const u8) }, 0);
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        assert_eq!(key.rights(), PkeyRights::PKEY_DISABLE_WRITE);
+        key.set_rights(PkeyRights::empty());
+        assert_eq!(key.rights(), PkeyRights::empty());
+        unsafe { *(ptr as *mut u8) = 1 };
+        key.set_rights(PkeyRights::PKEY_DISABLE_ACCESS);
+        assert_eq!(key.rights(), PkeyRights::PKEY_DISABLE_ACCESS);
+        key.set_rights(PkeyRights::empty());
+        assert_eq!(unsafe { *(ptr as *const u8) }, 1);
+    }
+
+    unsafe { munmap(ptr, len) }.unwrap();
+}
+
+#[test]
+#[cfg(all(
+    target_os = "linux",
+    any(target_env = "gnu", target_env = "musl"),
+    any(target_arch = "x86", target_arch = "x86_64")
+))]
+fn test_pkru() {
+    use nix::sys::mman::{read_pkru, write_pkru, PkeyRights, ProtectionKey};
+
+    // Without support for protection keys the instructions would fault.
+    if ProtectionKey::alloc(PkeyRights::empty()).is_err() {
+        skip!("protection keys are not supported");
+    }
+    let pkru = unsafe { read_pkru() };
+    // Key 0 covers all other memory, and must stay accessible.
+    assert_eq!(pkru & 0b11, 0);
+    unsafe { write_pkru(pkru) };
+    assert_eq!(unsafe { read_pkru() }, pkru);
+}
+
+#[test]
+fn test_mmap_region_anonymous() {
+    use nix::errno::Errno;
+    use nix::sys::mman::{MmapAdvise, MmapRegion, MsFlags};
+
+    let len = 4096;
+    let mut region = MmapRegion::anonymous(
+        NonZeroUsize::new(len).unwrap(),
+        ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+        MapFlags::MAP_PRIVATE,
+    )
+    .unwrap();
+    assert_eq!(region.size(), len);
+    assert!(region.as_slice().unwrap().iter().all(|&b| b == 0));
+    region.as_mut_slice().unwrap().fill(1);
+    region.sync(MsFlags::MS_SYNC).unwrap();
+
+    region.protect(ProtFlags::PROT_NONE).unwrap();
+    assert_eq!(region.prot(), ProtFlags::PROT_NONE);
+    assert!(region.as_slice().is_none());
+    region.protect(ProtFlags::PROT_READ).unwrap();
+    assert!(region.as_mut_slice().is_none());
+    assert!(region.as_slice().unwrap().iter().all(|&b| b == 1));
+
+    region.advise(MmapAdvise::MADV_WILLNEED).unwrap();
+    assert_eq!(region.advise(MmapAdvise::MADV_DONTNEED), Err(Errno::EINVAL));
+    assert!(region.as_slice().unwrap().iter().all(|&b| b == 1));
+    // Locking can fail with ENOMEM or EPERM under a low RLIMIT_MEMLOCK.
+    if region.lock().is_ok() {
+        region.unlock().unwrap();
+    }
+}
+
+#[test]
+fn test_mmap_region_map_fixed() {
+    use nix::errno::Errno;
+    use nix::sys::mman::MmapRegion;
+
+    let res = MmapRegion::anonymous(
+        NonZeroUsize::new(4096).unwrap(),
+        ProtFlags::PROT_READ,
+        MapFlags::MAP_PRIVATE | MapFlags::MAP_FIXED,
+    );
+    assert_eq!(res.unwrap_err(), Errno::EINVAL);
+}
+
+#[test]
+fn test_mmap_region_shared() {
+    use nix::errno::Errno;
+    use nix::sys::mman::MmapRegion;
+
+    let res = MmapRegion::anonymous(
+        NonZeroUsize::new(4096).unwrap(),
+        ProtFlags::PROT_READ,
+        MapFlags::MAP_SHARED,
+    );
+    assert_eq!(res.unwrap_err(), Errno::EINVAL);
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_mmap_region_advise_unchecked() {
+    use nix::sys::mman::{MmapAdvise, MmapRegion};
+
+    let mut region = MmapRegion::anonymous(
+        NonZeroUsize::new(4096).unwrap(),
+        ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+        MapFlags::MAP_PRIVATE,
+    )
+    .unwrap();
+    region.as_mut_slice().unwrap().fill(1);
+    unsafe { region.advise_unchecked(MmapAdvise::MADV_DONTNEED) }.unwrap();
+    // Private anonymous pages are zero-filled again.
+    assert!(region.as_slice().unwrap().iter().all(|&b| b == 0));
+}
+
+#[test]
+fn test_mmap_region_file() {
+    use nix::sys::mman::{MmapRegion, MsFlags};
+    use std::io::{Read, Seek, SeekFrom, Write};
+
+    let mut file = tempfile::tempfile().unwrap();
+    file.write_all(b"hello, world").unwrap();
+    let mut region = unsafe {
+        MmapRegion::file(
+            &file,
+            0,
+            NonZeroUsize::new(12).unwrap(),
+            ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+            MapFlags::MAP_SHARED,
+        )
+    }
+    .unwrap();
+    assert_eq!(region.as_slice().unwrap(), b"hello, world");
+    region.as_mut_slice().unwrap()[..5].copy_from_slice(b"HELLO");
+    region.sync(MsFlags::MS_SYNC).unwrap();
+    drop(region);
+
+    let mut buf = String::new();
+    file.seek(SeekFrom::Start(0)).unwrap();
+    file.read_to_string(&mut buf).unwrap();
+    assert_eq!(buf, "HELLO, world");
+}
+
+#[test]
+fn test_mmap_region_split() {
+    use nix::errno::Errno;
+    use nix::sys::mman::MmapRegion;
+    use nix::unistd::{sysconf, SysconfVar};
+
+    let page_size = sysconf(SysconfVar::PAGE_SIZE).unwrap().unwrap() as usize;
+    let mut head = MmapRegion::anonymous(
+        NonZeroUsize::new(3 * page_size).unwrap(),
+        ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+        MapFlags::MAP_PRIVATE,
+    )
+    .unwrap();
+    head.as_mut_slice().unwrap().fill(1);
+    assert_eq!(head.split(0).unwrap_err(), Errno::EINVAL);
+    assert_eq!(head.split(page_size + 1).unwrap_err(), Errno::EINVAL);
+    assert_eq!(head.split(3 * page_size).unwrap_err(), Errno::EINVAL);
+
+    let mut tail = head.split(page_size).unwrap();
+    assert_eq!(head.size(), page_size);
+    assert_eq!(tail.size(), 2 * page_size);
+    assert_eq!(tail.as_ptr() as usize, head.as_ptr() as usize + page_size);
+    // The halves are independent mappings.
+    tail.protect(ProtFlags::PROT_READ).unwrap();
+    head.as_mut_slice().unwrap().fill(2);
+    assert!(tail.as_slice().unwrap().iter().all(|&b| b == 1));
+    drop(head);
+    assert!(tail.as_slice().unwrap().iter().all(|&b| b == 1));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_mmap_region_remap() {
+    use nix::sys::mman::{MRemapFlags, MmapRegion};
+
+    let mut region = MmapRegion::anonymous(
+        NonZeroUsize::new(4096).unwrap(),
+        ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+        MapFlags::MAP_PRIVATE,
+    )
+    .unwrap();
+    region.as_mut_slice().unwrap().fill(1);
+    region
+        .remap(
+            NonZeroUsize::new(3 * 4096).unwrap(),
+            MRemapFlags::MREMAP_MAYMOVE,
+        )
+        .unwrap();
+    assert_eq!(region.size(), 3 * 4096);
+    let slice = region.as_slice().unwrap();
+    assert!(slice[..4096].iter().all(|&b| b == 1));
+    assert!(slice[4096..].iter().all(|&b| b == 0));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_mmap_region_fixed_noreplace() {
+    use nix::errno::Errno;
+    use nix::sys::mman::MmapRegion;
+
+    let len = NonZeroUsize::new(4096).unwrap();
+    let region =
+        MmapRegion::anonymous(len, ProtFlags::PROT_READ, MapFlags::MAP_PRIVATE)
+            .unwrap();
+    let addr = NonZeroUsize::new(region.as_ptr() as usize).unwrap();
+    let res = MmapRegion::fixed_noreplace(
+        addr,
+        len,
+        ProtFlags::PROT_READ,
+        MapFlags::MAP_PRIVATE,
+    );
+    assert_eq!(res.unwrap_err(), Errno::EEXIST);
+
+    drop(region);
+    let region = MmapRegion::fixed_noreplace(
+        addr,
+        len,
+        ProtFlags::PROT_READ,
+        MapFlags::MAP_PRIVATE,
+    )
+    .unwrap();
+    assert_eq!(region.as_ptr() as usize, usize::from(addr));
+}
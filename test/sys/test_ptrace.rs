@@ -273,3 +273,696 @@ fn test_ptrace_syscall() {
         }
     }
 }
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+#[test]
+fn test_ptrace_get_syscall_info() {
+    use nix::sys::ptrace::SyscallInfo;
+    use nix::sys::signal::kill;
+    use nix::sys::signal::Signal;
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    require_capability!("test_ptrace_get_syscall_info", CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock();
+
+    match unsafe { fork() }.expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            // first sigstop until parent is ready to continue
+            let pid = getpid();
+            kill(pid, Signal::SIGSTOP).unwrap();
+            unsafe {
+                ::libc::syscall(::libc::SYS_getppid);
+                ::libc::_exit(0);
+            }
+        }
+
+        Parent { child } => {
+            assert_eq!(
+                waitpid(child, None),
+                Ok(WaitStatus::Stopped(child, Signal::SIGSTOP))
+            );
+            ptrace::setoptions(child, Options::PTRACE_O_TRACESYSGOOD).unwrap();
+
+            match ptrace::get_syscall_info(child) {
+                Err(Errno::EIO) => {
+                    ptrace::kill(child).unwrap();
+                    waitpid(child, None).unwrap();
+                    skip!("PTRACE_GET_SYSCALL_INFO requires Linux 5.3");
+                }
+                res => assert_eq!(res.unwrap(), SyscallInfo::None),
+            }
+
+            // The first syscall-stops belong to the tail of `kill`, so skip
+            // ahead to the entry of `getppid`.
+            let entry = loop {
+                ptrace::syscall(child, None).unwrap();
+                let status = waitpid(child, None).unwrap();
+                assert_eq!(status, WaitStatus::PtraceSyscall(child));
+                match status.syscall_info().unwrap().unwrap() {
+                    info @ SyscallInfo::Entry { nr, .. }
+                        if nr == ::libc::SYS_getppid as u64 =>
+                    {
+                        break info
+                    }
+                    _ => (),
+                }
+            };
+            if let SyscallInfo::Entry { ip, sp, .. } = entry {
+                assert_ne!(ip, 0);
+                assert_ne!(sp, 0);
+            }
+
+            ptrace::syscall(child, None).unwrap();
+            let status = waitpid(child, None).unwrap();
+            assert_eq!(
+                status.syscall_info().unwrap().unwrap(),
+                SyscallInfo::Exit {
+                    rval: getpid().as_raw().into(),
+                    is_error: false
+                }
+            );
+            assert!(WaitStatus::Stopped(child, Signal::SIGSTOP)
+                .syscall_info()
+                .is_none());
+
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    target_env = "gnu",
+    any(target_arch = "x86_64", target_arch = "x86", target_arch = "aarch64")
+))]
+#[test]
+fn test_ptrace_getregset() {
+    use nix::sys::ptrace::{regset, NoteType, Registers};
+    use nix::sys::signal::kill;
+    use nix::sys::signal::Signal;
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    require_capability!("test_ptrace_getregset", CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock();
+
+    match unsafe { fork() }.expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            // first sigstop until parent is ready to continue
+            let pid = getpid();
+            kill(pid, Signal::SIGSTOP).unwrap();
+            unsafe {
+                ::libc::syscall(::libc::SYS_getppid);
+                ::libc::_exit(0);
+            }
+        }
+
+        Parent { child } => {
+            assert_eq!(
+                waitpid(child, None),
+                Ok(WaitStatus::Stopped(child, Signal::SIGSTOP))
+            );
+
+            let regs = ptrace::getregset::<regset::NT_PRSTATUS>(child).unwrap();
+            assert_ne!(regs.ip(), 0);
+            assert_ne!(regs.sp(), 0);
+            #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+            assert_eq!(regs, ptrace::getregs(child).unwrap());
+            ptrace::setregset::<regset::NT_PRSTATUS>(child, &regs).unwrap();
+
+            let fpregs =
+                ptrace::getregset::<regset::NT_PRFPREG>(child).unwrap();
+            let mut buf = vec![0u8; mem::size_of_val(&fpregs) + 64];
+            let len =
+                ptrace::getregset_bytes(child, NoteType::NT_PRFPREG, &mut buf)
+                    .unwrap();
+            assert_eq!(len, mem::size_of_val(&fpregs));
+            ptrace::setregset_bytes(child, NoteType::NT_PRFPREG, &buf[..len])
+                .unwrap();
+
+            ptrace::setoptions(child, Options::PTRACE_O_TRACESYSGOOD).unwrap();
+            loop {
+                ptrace::syscall(child, None).unwrap();
+                assert_eq!(
+                    waitpid(child, None),
+                    Ok(WaitStatus::PtraceSyscall(child))
+                );
+                let regs =
+                    ptrace::getregset::<regset::NT_PRSTATUS>(child).unwrap();
+                if regs.syscall_number() == ::libc::SYS_getppid as u64 {
+                    break;
+                }
+            }
+            // Exit from getppid
+            ptrace::syscall(child, None).unwrap();
+            waitpid(child, None).unwrap();
+            let regs = ptrace::getregset::<regset::NT_PRSTATUS>(child).unwrap();
+            assert_eq!(regs.return_value(), getpid().as_raw() as u64);
+
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_ptrace_tracee_memory() {
+    use nix::sys::ptrace::TraceeMemory;
+    use nix::sys::signal::kill;
+    use nix::sys::signal::Signal;
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+    use std::ffi::CString;
+    use std::ptr;
+
+    require_capability!("test_ptrace_tracee_memory", CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock();
+
+    let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    // Two readable pages followed by an unmapped one.  The second page is
+    // read only.
+    let map = unsafe {
+        let map = libc::mmap(
+            ptr::null_mut(),
+            3 * page,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+            -1,
+            0,
+        );
+        assert_ne!(map, libc::MAP_FAILED);
+        libc::munmap(map.cast::<u8>().add(2 * page).cast(), page);
+        let bytes = std::slice::from_raw_parts_mut(map.cast::<u8>(), 2 * page);
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = (i % 251) as u8 + 1;
+        }
+        bytes[page + 16] = 0;
+        libc::mprotect(
+            map.cast::<u8>().add(page).cast(),
+            page,
+            libc::PROT_READ,
+        );
+        map as usize
+    };
+
+    match unsafe { fork() }.expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            kill(getpid(), Signal::SIGSTOP).unwrap();
+            unsafe { ::libc::_exit(0) }
+        }
+
+        Parent { child } => {
+            assert_eq!(
+                waitpid(child, None),
+                Ok(WaitStatus::Stopped(child, Signal::SIGSTOP))
+            );
+            let mut mem = TraceeMemory::new(child);
+
+            // A read across the boundary between the two pages
+            let mut buf = [0u8; 32];
+            mem.read_bytes(map + page - 16, &mut buf).unwrap();
+            let expected: Vec<u8> = (page - 16..page + 16)
+                .map(|i| {
+                    if i == page + 16 {
+                        0
+                    } else {
+                        (i % 251) as u8 + 1
+                    }
+                })
+                .collect();
+            assert_eq!(&buf[..], &expected[..]);
+
+            let s = mem.read_cstring(map + page - 4, 4096).unwrap();
+            assert_eq!(s.as_bytes(), &expected[12..]);
+            let s = mem.read_cstring(map + page - 4, 2).unwrap();
+            assert_eq!(s, CString::new(&expected[12..14]).unwrap());
+
+            let word: u32 = unsafe { mem.read_struct(map) }.unwrap();
+            assert_eq!(word, u32::from_ne_bytes([1, 2, 3, 4]));
+
+            // The read stops precisely at the unmapped page.
+            let mut buf = vec![0u8; 64];
+            let err = mem.read_bytes(map + 2 * page - 8, &mut buf).unwrap_err();
+            assert_eq!(err.addr(), map + 2 * page);
+            assert_eq!(err.transferred(), 8);
+            let tail: Vec<u8> = (2 * page - 8..2 * page)
+                .map(|i| (i % 251) as u8 + 1)
+                .collect();
+            assert_eq!(buf[..8], tail[..]);
+
+            // Writes, including to the read only page
+            mem.write_bytes(map + page - 2, b"nix").unwrap();
+            let mut buf = [0u8; 3];
+            mem.read_bytes(map + page - 2, &mut buf).unwrap();
+            assert_eq!(&buf, b"nix");
+
+            let err = mem.write_bytes(map + 2 * page - 1, b"ab").unwrap_err();
+            assert_eq!(err.addr(), map + 2 * page);
+            assert_eq!(err.transferred(), 1);
+
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+            unsafe { libc::munmap(map as *mut libc::c_void, 2 * page) };
+        }
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    not(any(target_arch = "mips", target_arch = "mips64"))
+))]
+#[test]
+fn test_ptrace_tracer() {
+    use nix::sys::ptrace::{TraceEvent, TraceStatus, Tracer};
+    use nix::sys::signal::{kill, raise, Signal};
+    use nix::sys::wait::waitpid;
+    use nix::unistd::ForkResult::*;
+    use nix::unistd::{fork, pipe, read, write};
+
+    require_capability!("test_ptrace_tracer", CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock();
+
+    let (r, w) = pipe().unwrap();
+    match unsafe { fork() }.expect("Error: Fork Failed") {
+        Child => {
+            // Wait until the parent has attached.
+            read(r, &mut [0u8]).unwrap();
+            let grandchild = match unsafe { fork() }.unwrap() {
+                Child => unsafe { ::libc::_exit(3) },
+                Parent { child } => child,
+            };
+            waitpid(grandchild, None).unwrap();
+            raise(Signal::SIGSTOP).unwrap();
+            unsafe { ::libc::_exit(0) }
+        }
+
+        Parent { child } => {
+            let mut tracer = Tracer::new(
+                Options::PTRACE_O_TRACEFORK | Options::PTRACE_O_TRACEEXIT,
+            );
+            tracer.seize(child).unwrap();
+            assert_eq!(tracer.tracees().collect::<Vec<_>>(), [child]);
+            write(w, &[0u8]).unwrap();
+
+            let mut grandchild = None;
+            let mut events = Vec::new();
+            loop {
+                match tracer.wait().unwrap() {
+                    TraceStatus::Stopped(pid, event) => {
+                        if let TraceEvent::Fork { child: gc } = event {
+                            assert_eq!(pid, child);
+                            assert!(tracer.tracees().any(|t| t == gc));
+                            grandchild = Some(gc);
+                        }
+                        if let TraceEvent::Stop {
+                            group_stop: true, ..
+                        } = event
+                        {
+                            kill(child, Signal::SIGCONT).unwrap();
+                        }
+                        events.push((pid, event));
+                        tracer.resume(pid, event).unwrap();
+                    }
+                    TraceStatus::Exited(pid, code) => {
+                        if pid == child {
+                            assert_eq!(code, 0);
+                            break;
+                        }
+                        assert_eq!(Some(pid), grandchild);
+                        assert_eq!(code, 3);
+                    }
+                    status => panic!("unexpected {:?}", status),
+                }
+            }
+            let grandchild = grandchild.unwrap();
+            assert!(events.contains(&(grandchild, TraceEvent::Interrupt)));
+            assert!(events
+                .contains(&(grandchild, TraceEvent::Exit { status: 3 << 8 })));
+            assert!(events.contains(&(
+                child,
+                TraceEvent::Stop {
+                    signal: Signal::SIGSTOP,
+                    group_stop: false
+                }
+            )));
+            assert!(events.contains(&(
+                child,
+                TraceEvent::Stop {
+                    signal: Signal::SIGSTOP,
+                    group_stop: true
+                }
+            )));
+            assert!(events.contains(&(child, TraceEvent::Exit { status: 0 })));
+            assert_eq!(tracer.tracees().count(), 0);
+            nix::unistd::close(r).unwrap();
+            nix::unistd::close(w).unwrap();
+        }
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    not(any(target_arch = "mips", target_arch = "mips64"))
+))]
+#[test]
+fn test_ptrace_introspection() {
+    use nix::sys::signal::Signal;
+    use nix::sys::signal::{kill, raise, sigprocmask, SigSet, SigmaskHow};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    require_capability!("test_ptrace_introspection", CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock();
+
+    match unsafe { fork() }.expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            let mut mask = SigSet::empty();
+            mask.add(Signal::SIGUSR1);
+            sigprocmask(SigmaskHow::SIG_BLOCK, Some(&mask), None).unwrap();
+            raise(Signal::SIGUSR1).unwrap();
+
+            // An allow-all filter
+            let mut filter = [libc::sock_filter {
+                code: (libc::BPF_RET | libc::BPF_K) as u16,
+                jt: 0,
+                jf: 0,
+                k: libc::SECCOMP_RET_ALLOW,
+            }];
+            let prog = libc::sock_fprog {
+                len: 1,
+                filter: filter.as_mut_ptr(),
+            };
+            unsafe {
+                libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0);
+                libc::prctl(
+                    libc::PR_SET_SECCOMP,
+                    libc::SECCOMP_MODE_FILTER,
+                    &prog as *const libc::sock_fprog,
+                );
+            }
+
+            kill(getpid(), Signal::SIGSTOP).unwrap();
+            unsafe { ::libc::_exit(0) }
+        }
+
+        Parent { child } => {
+            assert_eq!(
+                waitpid(child, None),
+                Ok(WaitStatus::Stopped(child, Signal::SIGSTOP))
+            );
+
+            let pending = ptrace::peek_siginfo(child, false, 0..4).unwrap();
+            assert_eq!(pending.len(), 1);
+            assert_eq!(pending[0].si_signo, libc::SIGUSR1);
+            assert!(ptrace::peek_siginfo(child, true, 0..4)
+                .unwrap()
+                .is_empty());
+            let all =
+                ptrace::peek_siginfo(child, false, 0..usize::MAX).unwrap();
+            assert_eq!(all.len(), 1);
+
+            let mask = ptrace::get_sigmask(child).unwrap();
+            assert!(mask.contains(Signal::SIGUSR1));
+            ptrace::set_sigmask(child, &SigSet::empty()).unwrap();
+            assert!(!ptrace::get_sigmask(child)
+                .unwrap()
+                .contains(Signal::SIGUSR1));
+            // Blocking the signal again keeps the child from dying of it.
+            ptrace::set_sigmask(child, &mask).unwrap();
+
+            match ptrace::get_seccomp_filter(child, 0) {
+                Err(Errno::EACCES) | Err(Errno::EIO) => (),
+                res => {
+                    let filter = res.unwrap();
+                    assert_eq!(filter.len(), 1);
+                    assert_eq!(filter[0].k, libc::SECCOMP_RET_ALLOW);
+                    assert_eq!(
+                        ptrace::get_seccomp_filter(child, 1).unwrap_err(),
+                        Errno::ENOENT
+                    );
+                }
+            }
+
+            match ptrace::get_rseq_configuration(child) {
+                // Before Linux 5.13
+                Err(Errno::EIO) => (),
+                res => {
+                    let conf = res.unwrap();
+                    if conf.rseq_abi_pointer != 0 {
+                        assert!(conf.rseq_abi_size >= 32);
+                    }
+                }
+            }
+
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    target_env = "gnu",
+    any(target_arch = "x86_64", target_arch = "x86", target_arch = "aarch64")
+))]
+#[test]
+fn test_ptrace_watchpoint() {
+    use nix::sys::ptrace::watchpoint::{self, Slot, WatchKind};
+    use nix::sys::signal::kill;
+    use nix::sys::signal::Signal;
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static WATCHED: AtomicU32 = AtomicU32::new(0);
+
+    require_capability!("test_ptrace_watchpoint", CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock();
+
+    match unsafe { fork() }.expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            kill(getpid(), Signal::SIGSTOP).unwrap();
+            WATCHED.store(42, Ordering::SeqCst);
+            WATCHED.store(43, Ordering::SeqCst);
+            unsafe { ::libc::_exit(0) }
+        }
+
+        Parent { child } => {
+            assert_eq!(
+                waitpid(child, None),
+                Ok(WaitStatus::Stopped(child, Signal::SIGSTOP))
+            );
+            let addr = &WATCHED as *const AtomicU32 as usize;
+            let slots = watchpoint::slots(child, WatchKind::Write).unwrap();
+            if slots < 2 {
+                ptrace::kill(child).unwrap();
+                waitpid(child, None).unwrap();
+                skip!("No hardware watchpoints available");
+            }
+            assert_eq!(
+                watchpoint::set(child, 1, addr + 1, 4, WatchKind::Write)
+                    .unwrap_err(),
+                Errno::EINVAL
+            );
+            let slot =
+                watchpoint::set(child, 1, addr, 4, WatchKind::Write).unwrap();
+            assert_eq!(slot, Slot::Watchpoint(1));
+            assert_eq!(watchpoint::hit_slot(child).unwrap(), None);
+
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(
+                waitpid(child, None),
+                Ok(WaitStatus::Stopped(child, Signal::SIGTRAP))
+            );
+            assert_eq!(watchpoint::hit_slot(child).unwrap(), Some(slot));
+            let value = ptrace::read(child, addr as _).unwrap() as u32;
+            assert_eq!(value, 42);
+
+            // Without the watchpoint, the second write does not stop.
+            watchpoint::clear(child, slot).unwrap();
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg(any(
+    target_arch = "aarch64",
+    target_arch = "riscv64",
+    target_arch = "x86",
+    all(target_arch = "x86_64", target_pointer_width = "64")
+))]
+#[test]
+fn test_ptrace_sysno() {
+    use nix::sys::ptrace::Sysno;
+    use std::convert::TryFrom;
+
+    let sysno: Sysno = "openat".parse().unwrap();
+    assert_eq!(sysno, Sysno::openat);
+    assert_eq!(sysno.as_raw(), ::libc::SYS_openat);
+    assert_eq!(sysno.arity(), 4);
+    assert_eq!(sysno.to_string(), "openat");
+    assert_eq!(
+        Sysno::try_from(::libc::SYS_getpid as u64),
+        Ok(Sysno::getpid)
+    );
+    assert_eq!(Sysno::getpid.arity(), 0);
+    assert_eq!("no_such_syscall".parse::<Sysno>(), Err(Errno::EINVAL));
+    assert_eq!(Sysno::try_from(u64::MAX), Err(Errno::EINVAL));
+
+    for sysno in Sysno::iterator() {
+        assert_eq!(sysno.name().parse(), Ok(sysno));
+        assert_eq!(Sysno::try_from(sysno.as_raw() as u64), Ok(sysno));
+        assert!(sysno.arity() <= 6);
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg(any(
+    target_arch = "aarch64",
+    target_arch = "riscv64",
+    target_arch = "x86",
+    all(target_arch = "x86_64", target_pointer_width = "64")
+))]
+#[test]
+fn test_ptrace_sysno_table() {
+    use nix::sys::ptrace::Sysno;
+
+    // The tables are complete up to the end of each range of numbers.
+    #[cfg(target_arch = "aarch64")]
+    let expected = [
+        (Sysno::renameat, 38),
+        (Sysno::sendfile, 71),
+        (Sysno::sync_file_range, 84),
+        (Sysno::setrlimit, 164),
+        (Sysno::fadvise64, 223),
+        (Sysno::io_pgetevents, 292),
+        (Sysno::set_mempolicy_home_node, 450),
+    ];
+    #[cfg(target_arch = "riscv64")]
+    let expected = [
+        (Sysno::riscv_flush_icache, 259),
+        (Sysno::io_pgetevents, 292),
+        (Sysno::kexec_file_load, 294),
+        (Sysno::set_mempolicy_home_node, 450),
+    ];
+    #[cfg(target_arch = "x86")]
+    let expected = [
+        (Sysno::arch_prctl, 384),
+        (Sysno::io_pgetevents, 385),
+        (Sysno::msgctl, 402),
+        (Sysno::sched_rr_get_interval_time64, 423),
+        (Sysno::set_mempolicy_home_node, 450),
+    ];
+    #[cfg(target_arch = "x86_64")]
+    let expected = [
+        (Sysno::io_pgetevents, 333),
+        (Sysno::rseq, 334),
+        (Sysno::set_mempolicy_home_node, 450),
+    ];
+    for (sysno, nr) in expected {
+        assert_eq!(sysno.as_raw(), nr);
+    }
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+#[cfg(any(
+    target_arch = "aarch64",
+    target_arch = "riscv64",
+    target_arch = "x86",
+    all(target_arch = "x86_64", target_pointer_width = "64")
+))]
+#[test]
+fn test_ptrace_format_syscall_entry() {
+    use nix::sys::ptrace::{format_syscall_entry, SyscallInfo, Sysno};
+    use nix::sys::signal::kill;
+    use nix::sys::signal::Signal;
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+    use std::convert::TryFrom;
+
+    require_capability!("test_ptrace_format_syscall_entry", CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock();
+
+    let path = b"/nonexistent/nix-format-syscall-entry\0";
+    match unsafe { fork() }.expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            kill(getpid(), Signal::SIGSTOP).unwrap();
+            unsafe {
+                ::libc::syscall(
+                    ::libc::SYS_openat,
+                    ::libc::AT_FDCWD,
+                    path.as_ptr(),
+                    ::libc::O_WRONLY | ::libc::O_CREAT | ::libc::O_CLOEXEC,
+                    0o644,
+                );
+                ::libc::_exit(0);
+            }
+        }
+
+        Parent { child } => {
+            assert_eq!(
+                waitpid(child, None),
+                Ok(WaitStatus::Stopped(child, Signal::SIGSTOP))
+            );
+            ptrace::setoptions(child, Options::PTRACE_O_TRACESYSGOOD).unwrap();
+
+            let entry = loop {
+                ptrace::syscall(child, None).unwrap();
+                assert_eq!(
+                    waitpid(child, None),
+                    Ok(WaitStatus::PtraceSyscall(child))
+                );
+                match ptrace::get_syscall_info(child) {
+                    Err(Errno::EIO) => {
+                        ptrace::kill(child).unwrap();
+                        waitpid(child, None).unwrap();
+                        skip!("PTRACE_GET_SYSCALL_INFO requires Linux 5.3");
+                    }
+                    Ok(SyscallInfo::Entry { nr, args, .. })
+                        if nr == ::libc::SYS_openat as u64 =>
+                    {
+                        break (nr, args)
+                    }
+                    res => {
+                        res.unwrap();
+                    }
+                }
+            };
+            let sysno = Sysno::try_from(entry.0).unwrap();
+            assert_eq!(sysno, Sysno::openat);
+            let formatted = format_syscall_entry(child, sysno, &entry.1);
+            assert_eq!(
+                formatted,
+                "openat(AT_FDCWD, \"/nonexistent/nix-format-syscall-entry\", \
+                 O_WRONLY | O_CLOEXEC | O_CREAT, 0o644)"
+            );
+
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
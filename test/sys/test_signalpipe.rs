@@ -0,0 +1,71 @@
+use nix::sys::signal::{self, raise, SigHandler, SigSet, Signal};
+use nix::sys::signalpipe::{SigPipeFlags, SignalPipe};
+
+#[test]
+fn test_signalpipe() {
+    let _m = crate::SIGNAL_MTX.lock();
+
+    let mut mask = SigSet::empty();
+    mask.add(Signal::SIGUSR2);
+    let mut pipe = SignalPipe::new(&mask).unwrap();
+
+    raise(Signal::SIGUSR2).unwrap();
+    assert_eq!(pipe.read_signal().unwrap(), Some(Signal::SIGUSR2));
+}
+
+#[test]
+fn test_signalpipe_multiple_subscribers() {
+    let _m = crate::SIGNAL_MTX.lock();
+
+    let mut mask = SigSet::empty();
+    mask.add(Signal::SIGUSR2);
+    let mut first =
+        SignalPipe::with_flags(&mask, SigPipeFlags::O_NONBLOCK).unwrap();
+    let mut second =
+        SignalPipe::with_flags(&mask, SigPipeFlags::O_NONBLOCK).unwrap();
+
+    raise(Signal::SIGUSR2).unwrap();
+    assert_eq!(first.read_signal().unwrap(), Some(Signal::SIGUSR2));
+    assert_eq!(second.read_signal().unwrap(), Some(Signal::SIGUSR2));
+
+    drop(first);
+    raise(Signal::SIGUSR2).unwrap();
+    assert_eq!(second.read_signal().unwrap(), Some(Signal::SIGUSR2));
+    assert_eq!(second.read_signal().unwrap(), None);
+}
+
+#[test]
+fn test_signalpipe_iter() {
+    let _m = crate::SIGNAL_MTX.lock();
+
+    let mut mask = SigSet::empty();
+    mask.add(Signal::SIGUSR2);
+    let pipe = SignalPipe::with_flags(&mask, SigPipeFlags::O_NONBLOCK).unwrap();
+
+    raise(Signal::SIGUSR2).unwrap();
+    let signals: Vec<_> = pipe.collect::<nix::Result<_>>().unwrap();
+    assert_eq!(signals, [Signal::SIGUSR2]);
+}
+
+#[test]
+fn test_signalpipe_restores_previous_action() {
+    let _m = crate::SIGNAL_MTX.lock();
+
+    let old =
+        unsafe { signal::signal(Signal::SIGUSR2, SigHandler::SigIgn) }.unwrap();
+
+    let mut mask = SigSet::empty();
+    mask.add(Signal::SIGUSR2);
+    let pipe = SignalPipe::new(&mask).unwrap();
+    drop(pipe);
+
+    let restored = unsafe { signal::signal(Signal::SIGUSR2, old) }.unwrap();
+    assert_eq!(restored, SigHandler::SigIgn);
+}
+
+#[test]
+fn test_signalpipe_uncatchable() {
+    let mut mask = SigSet::empty();
+    mask.add(Signal::SIGKILL);
+    SignalPipe::new(&mask).unwrap_err();
+}
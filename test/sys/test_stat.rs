@@ -27,3 +27,72 @@ fn test_chflags() {
 
     assert_eq!(commanded, changed);
 }
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_inode_flags() {
+    use nix::errno::Errno;
+    use nix::sys::stat::{get_inode_flags, set_inode_flags, InodeFlags};
+    use std::fs::OpenOptions;
+    use tempfile::NamedTempFile;
+
+    use crate::*;
+
+    let tmp = NamedTempFile::new().unwrap();
+    let f = tmp.as_file();
+    let initial = match get_inode_flags(f) {
+        Err(Errno::ENOTTY) | Err(Errno::EOPNOTSUPP) => {
+            skip!("the file system does not support inode flags");
+        }
+        res => res.unwrap(),
+    };
+    let flags = initial | InodeFlags::FS_NOATIME_FL | InodeFlags::FS_NODUMP_FL;
+    set_inode_flags(f, flags).unwrap();
+    assert_eq!(get_inode_flags(f).unwrap(), flags);
+
+    require_capability!("test_inode_flags", CAP_LINUX_IMMUTABLE);
+    set_inode_flags(f, initial | InodeFlags::FS_APPEND_FL).unwrap();
+    // Append-only files can only be opened for appending.
+    let res = OpenOptions::new().write(true).open(tmp.path());
+    assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::EPERM));
+    OpenOptions::new().append(true).open(tmp.path()).unwrap();
+    set_inode_flags(f, initial).unwrap();
+    assert_eq!(get_inode_flags(f).unwrap(), initial);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_fsxattr() {
+    use nix::errno::Errno;
+    use nix::sys::stat::{
+        get_fsxattr, get_inode_flags, set_fsxattr, FsXFlags, InodeFlags,
+    };
+
+    use crate::*;
+
+    let f = tempfile::tempfile().unwrap();
+    let mut attr = match get_fsxattr(&f) {
+        Err(Errno::ENOTTY) | Err(Errno::EOPNOTSUPP) => {
+            skip!("the file system does not support FS_IOC_FSGETXATTR");
+        }
+        res => res.unwrap(),
+    };
+    assert!(!attr.xflags.contains(FsXFlags::FS_XFLAG_NOATIME));
+    attr.xflags |= FsXFlags::FS_XFLAG_NOATIME;
+    set_fsxattr(&f, &attr).unwrap();
+    assert_eq!(get_fsxattr(&f).unwrap(), attr);
+    // They are the same attributes as the inode flags.
+    assert!(get_inode_flags(&f)
+        .unwrap()
+        .contains(InodeFlags::FS_NOATIME_FL));
+
+    // Project ids need support from the file system.
+    attr.projid = 42;
+    match set_fsxattr(&f, &attr) {
+        Err(Errno::EOPNOTSUPP) | Err(Errno::EINVAL) => (),
+        res => {
+            res.unwrap();
+            assert_eq!(get_fsxattr(&f).unwrap().projid, 42);
+        }
+    }
+}
@@ -67,3 +67,85 @@ pub fn test_timerfd_unset() {
 
     assert!(timer.get().unwrap().is_none());
 }
+
+#[test]
+pub fn test_timerfd_expiration_count() {
+    use nix::sys::timerfd::TimerEvent;
+
+    let timer =
+        TimerFd::new(ClockId::CLOCK_MONOTONIC, TimerFlags::empty()).unwrap();
+
+    timer
+        .set(
+            Expiration::Interval(TimeSpec::milliseconds(10)),
+            TimerSetTimeFlags::empty(),
+        )
+        .unwrap();
+
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    match timer.wait().unwrap() {
+        TimerEvent::Expired(n) => assert!(n > 1),
+        TimerEvent::ClockChanged => panic!("unexpected clock change"),
+    }
+}
+
+#[test]
+pub fn test_timerfd_try_wait() {
+    use nix::sys::timerfd::TimerEvent;
+
+    let timer =
+        TimerFd::new(ClockId::CLOCK_MONOTONIC, TimerFlags::empty()).unwrap();
+
+    assert_eq!(timer.try_wait().unwrap(), None);
+
+    timer
+        .set(
+            Expiration::OneShot(TimeSpec::milliseconds(10)),
+            TimerSetTimeFlags::empty(),
+        )
+        .unwrap();
+    assert_eq!(timer.try_wait().unwrap(), None);
+
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    assert_eq!(timer.try_wait().unwrap(), Some(TimerEvent::Expired(1)));
+    assert_eq!(timer.try_wait().unwrap(), None);
+}
+
+#[test]
+pub fn test_timerfd_set_at() {
+    use nix::sys::timerfd::TimerEvent;
+    use nix::time::{clock_gettime, ClockId as Clock};
+
+    let timer =
+        TimerFd::new(ClockId::CLOCK_REALTIME, TimerFlags::empty()).unwrap();
+
+    let before = Instant::now();
+    let now = clock_gettime(Clock::CLOCK_REALTIME).unwrap();
+    timer
+        .set_at(
+            now + TimeSpec::milliseconds(100),
+            TimerSetTimeFlags::TFD_TIMER_CANCEL_ON_SET,
+        )
+        .unwrap();
+
+    // Nobody should be setting the clock during the test.
+    assert_eq!(timer.wait().unwrap(), TimerEvent::Expired(1));
+    assert!(before.elapsed().as_millis() > 90);
+}
+
+#[test]
+pub fn test_timerfd_alarm_clock() {
+    use crate::*;
+    use nix::errno::Errno;
+
+    match TimerFd::new(ClockId::CLOCK_BOOTTIME_ALARM, TimerFlags::empty()) {
+        Err(Errno::EPERM) => {
+            skip!("CLOCK_BOOTTIME_ALARM requires CAP_WAKE_ALARM");
+        }
+        Err(Errno::EINVAL) => {
+            skip!("CLOCK_BOOTTIME_ALARM is not supported");
+        }
+        res => assert!(res.unwrap().get().unwrap().is_none()),
+    }
+}
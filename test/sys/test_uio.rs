@@ -220,6 +220,80 @@ fn test_preadv() {
     assert_eq!(all, expected);
 }
 
+#[test]
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn test_pwritev2() {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let tempdir = tempdir().unwrap();
+    let path = tempdir.path().join("pwritev2_test_file");
+    let mut file = OpenOptions::new()
+        .write(true)
+        .read(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+        .unwrap();
+
+    let iovecs = [IoSlice::new(b"ab"), IoSlice::new(b"cd")];
+    assert_eq!(
+        pwritev2(&file, &iovecs, Some(2), RwfFlags::RWF_DSYNC),
+        Ok(4)
+    );
+    // Without an offset, the file offset is used and advanced.
+    let iovecs = [IoSlice::new(b"xy")];
+    assert_eq!(pwritev2(&file, &iovecs, None, RwfFlags::empty()), Ok(2));
+    assert_eq!(file.stream_position().unwrap(), 2);
+    let iovecs = [IoSlice::new(b"!")];
+    assert_eq!(
+        pwritev2(&file, &iovecs, Some(0), RwfFlags::RWF_APPEND),
+        Ok(1)
+    );
+
+    let mut contents = Vec::new();
+    file.seek(SeekFrom::Start(0)).unwrap();
+    file.read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, b"xyabcd!");
+}
+
+#[test]
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn test_preadv2() {
+    use nix::errno::Errno;
+    use std::io::{Seek, Write};
+
+    let tempdir = tempdir().unwrap();
+    let path = tempdir.path().join("preadv2_test_file");
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+        .unwrap();
+    file.write_all(b"0123456789").unwrap();
+
+    let mut buf = [0; 4];
+    let mut iovecs = [IoSliceMut::new(&mut buf)];
+    // The data was just written, so it is in the page cache.
+    match preadv2(&file, &mut iovecs, Some(3), RwfFlags::RWF_NOWAIT) {
+        Err(Errno::EOPNOTSUPP) => (),
+        res => assert_eq!(res, Ok(4)),
+    }
+    assert_eq!(
+        preadv2(&file, &mut iovecs, Some(3), RwfFlags::empty()),
+        Ok(4)
+    );
+    assert_eq!(&buf, b"3456");
+
+    // Without an offset, the file offset is used and advanced.
+    file.rewind().unwrap();
+    let mut iovecs = [IoSliceMut::new(&mut buf)];
+    assert_eq!(preadv2(&file, &mut iovecs, None, RwfFlags::empty()), Ok(4));
+    assert_eq!(&buf, b"0123");
+    assert_eq!(file.stream_position().unwrap(), 4);
+}
+
 #[test]
 #[cfg(all(target_os = "linux", not(target_env = "uclibc")))]
 // uclibc doesn't implement process_vm_readv
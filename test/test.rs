@@ -26,6 +26,8 @@ mod test_nix_path;
 #[cfg(target_os = "freebsd")]
 mod test_nmount;
 mod test_poll;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod test_proc;
 #[cfg(not(any(
     target_os = "redox",
     target_os = "fuchsia",
@@ -62,6 +64,20 @@ mod test_time;
     feature = "signal"
 ))]
 mod test_timer;
+#[cfg(any(
+    all(
+        target_os = "linux",
+        not(target_env = "musl"),
+        any(
+            target_arch = "aarch64",
+            target_arch = "s390x",
+            target_arch = "x86",
+            target_arch = "x86_64"
+        )
+    ),
+    all(target_os = "freebsd", target_arch = "x86_64")
+))]
+mod test_ucontext;
 mod test_unistd;
 
 use nix::unistd::{chdir, getcwd, read};
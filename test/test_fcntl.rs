@@ -234,6 +234,7 @@ mod linux_android {
     use std::io::IoSlice;
     use std::os::unix::prelude::*;
 
+    use nix::errno::Errno;
     use nix::fcntl::*;
     use nix::unistd::{close, pipe, read, write};
 
@@ -368,6 +369,39 @@ mod linux_android {
         assert_eq!(100, read(fd, &mut buf).unwrap());
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sync_file_range() {
+        let mut tmp = tempfile().unwrap();
+        tmp.write_all(&[1; 8192]).unwrap();
+
+        sync_file_range(
+            &tmp,
+            0,
+            4096,
+            SyncFileRangeFlags::SYNC_FILE_RANGE_WRITE,
+        )
+        .unwrap();
+        sync_file_range(&tmp, 0, 0, SyncFileRangeFlags::all()).unwrap();
+        assert_eq!(
+            sync_file_range(&tmp, -1, 0, SyncFileRangeFlags::all()),
+            Err(Errno::EINVAL)
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_readahead() {
+        let mut tmp = tempfile().unwrap();
+        tmp.write_all(&[1; 8192]).unwrap();
+        readahead(&tmp, 0, 8192).unwrap();
+
+        let (rd, wr) = pipe().unwrap();
+        let rd = unsafe { OwnedFd::from_raw_fd(rd) };
+        assert_eq!(readahead(&rd, 0, 8192), Err(Errno::EINVAL));
+        close(wr).unwrap();
+    }
+
     // The tests below are disabled for the listed targets
     // due to OFD locks not being available in the kernel/libc
     // versions used in the CI environment, probably because
@@ -563,3 +597,438 @@ mod test_posix_fallocate {
         }
     }
 }
+
+#[test]
+#[cfg(not(target_os = "redox"))]
+fn test_file_lock() {
+    use nix::fcntl::{FileLock, LockType};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::{fork, getpid, ForkResult};
+
+    let _m = crate::FORK_MTX.lock();
+
+    let tmp = NamedTempFile::new().unwrap();
+    let file = tmp.as_file();
+    let lock = FileLock::new(LockType::Write).start(10).len(10);
+    let guard = lock.lock(file).unwrap();
+    // POSIX locks never conflict with locks of the same process.
+    assert_eq!(lock.query(file).unwrap(), None);
+
+    let parent = getpid();
+    match unsafe { fork() }.unwrap() {
+        ForkResult::Child => {
+            let file = File::open(tmp.path()).unwrap();
+            let ok = FileLock::new(LockType::Read)
+                .start(15)
+                .query(&file)
+                .map_or(false, |conflict| {
+                    conflict.map_or(false, |c| {
+                        c.lock_type == LockType::Write
+                            && c.start == 10
+                            && c.len == 10
+                            && c.pid == Some(parent)
+                    })
+                });
+            let free = FileLock::new(LockType::Read)
+                .len(10)
+                .query(&file)
+                .map_or(false, |conflict| conflict.is_none());
+            let busy = FileLock::new(LockType::Read).start(19).lock(&file);
+            let ok = ok
+                && free
+                && matches!(busy, Err(Errno::EAGAIN) | Err(Errno::EACCES));
+            unsafe { libc::_exit(if ok { 0 } else { 1 }) };
+        }
+        ForkResult::Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+
+    // Once released, nothing conflicts anymore.
+    let file = guard.unlock().unwrap();
+    assert_eq!(FileLock::new(LockType::Write).query(file).unwrap(), None);
+}
+
+#[test]
+#[cfg(all(target_os = "linux", not(target_env = "musl")))]
+#[cfg_attr(target_env = "uclibc", ignore)]
+fn test_file_lock_ofd() {
+    use nix::fcntl::{FileLock, LockType};
+
+    let tmp = NamedTempFile::new().unwrap();
+    let file1 = File::options().write(true).open(tmp.path()).unwrap();
+    let file2 = File::options().write(true).open(tmp.path()).unwrap();
+
+    let lock = FileLock::new(LockType::Write).ofd(true).len(10);
+    let guard = lock.lock(&file1).unwrap();
+    // Open file description locks conflict between files of a process.
+    let conflict = lock.query(&file2).unwrap().unwrap();
+    assert_eq!(conflict.lock_type, LockType::Write);
+    assert_eq!((conflict.start, conflict.len), (0, 10));
+    assert_eq!(conflict.pid, None);
+    assert_eq!(lock.lock(&file2).unwrap_err(), Errno::EAGAIN);
+    let other = lock.start(10).lock(&file2).unwrap();
+
+    drop(guard);
+    let guard = lock.lock(&file2).unwrap();
+    drop(other);
+    drop(guard);
+    assert_eq!(lock.query(&file1).unwrap(), None);
+}
+
+#[test]
+#[cfg(all(target_os = "linux", not(target_env = "musl")))]
+#[cfg_attr(target_env = "uclibc", ignore)]
+fn test_file_lock_relative_range() {
+    use nix::fcntl::{FileLock, LockType};
+    use nix::unistd::{lseek, Whence};
+    use std::os::unix::io::AsRawFd;
+
+    let tmp = NamedTempFile::new().unwrap();
+    let file1 = File::options().write(true).open(tmp.path()).unwrap();
+    let file2 = File::options().write(true).open(tmp.path()).unwrap();
+    (&file1).write_all(&[0; 100]).unwrap();
+
+    let lock = FileLock::new(LockType::Write).ofd(true).len(10);
+    let cur = lock
+        .whence(Whence::SeekCur)
+        .start(-50)
+        .lock(&file1)
+        .unwrap();
+    let end = lock.whence(Whence::SeekEnd).lock(&file1).unwrap();
+    let conflict = lock.start(50).query(&file2).unwrap().unwrap();
+    assert_eq!((conflict.start, conflict.len), (50, 10));
+    let conflict = lock.start(100).query(&file2).unwrap().unwrap();
+    assert_eq!((conflict.start, conflict.len), (100, 10));
+
+    // Moving the offset and growing the file does not change what is
+    // released.
+    lseek(file1.as_raw_fd(), 0, Whence::SeekSet).unwrap();
+    (&file1).write_all(&[0; 200]).unwrap();
+    drop(cur);
+    drop(end);
+    assert_eq!(lock.len(0).query(&file2).unwrap(), None);
+}
+
+#[test]
+#[cfg(not(target_os = "redox"))]
+fn test_flock_guard() {
+    use nix::fcntl::{Flock, FlockArg};
+
+    let tmp = NamedTempFile::new().unwrap();
+    let file1 = File::open(tmp.path()).unwrap();
+    let file2 = File::open(tmp.path()).unwrap();
+
+    let lock = Flock::lock(file1, FlockArg::LockExclusiveNonblock).unwrap();
+    let (file2, errno) =
+        Flock::lock(file2, FlockArg::LockSharedNonblock).unwrap_err();
+    assert_eq!(errno, Errno::EWOULDBLOCK);
+    let (file2, errno) = Flock::lock(file2, FlockArg::Unlock).unwrap_err();
+    assert_eq!(errno, Errno::EINVAL);
+
+    let file1 = lock.unlock().unwrap();
+    let lock2 = Flock::lock(file2, FlockArg::LockSharedNonblock).unwrap();
+    let lock1 = Flock::lock(file1, FlockArg::LockSharedNonblock).unwrap();
+    // Flock derefs to what it wraps.
+    assert!(lock1.metadata().unwrap().is_file());
+    drop(lock1);
+    drop(lock2);
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_lease() {
+    use nix::fcntl::{fcntl, lease_signalfd, FcntlArg, LeaseType};
+    use nix::sys::signal::Signal;
+    use std::convert::TryFrom;
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::os::unix::io::AsRawFd;
+
+    use crate::*;
+
+    // Read leases can only be taken on files nobody has open for writing.
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("leased");
+    File::create(&path).unwrap();
+    let file = File::open(&path).unwrap();
+    let fd = file.as_raw_fd();
+    match fcntl(fd, FcntlArg::F_SETLEASE(LeaseType::F_RDLCK)) {
+        // Leases are not supported by all file systems, like overlayfs.
+        Err(Errno::EINVAL) => {
+            skip!("the file system does not support leases");
+        }
+        res => res.unwrap(),
+    };
+    assert_eq!(
+        LeaseType::try_from(fcntl(fd, FcntlArg::F_GETLEASE).unwrap()),
+        Ok(LeaseType::F_RDLCK)
+    );
+    let mut sfd = lease_signalfd(&file, Signal::SIGUSR2).unwrap();
+    assert_eq!(
+        fcntl(fd, FcntlArg::F_GETSIG),
+        Ok(Signal::SIGUSR2 as libc::c_int)
+    );
+
+    // Opening the file for writing breaks the lease.
+    let res = File::options()
+        .write(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(&path);
+    assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::EWOULDBLOCK));
+    let info = sfd.read_signal().unwrap().unwrap();
+    assert_eq!(info.ssi_signo, Signal::SIGUSR2 as u32);
+    assert_eq!(info.ssi_fd, fd);
+    assert_eq!(
+        LeaseType::try_from(fcntl(fd, FcntlArg::F_GETLEASE).unwrap()),
+        Ok(LeaseType::F_UNLCK)
+    );
+    fcntl(fd, FcntlArg::F_SETLEASE(LeaseType::F_UNLCK)).unwrap();
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_f_setown_ex() {
+    use nix::fcntl::{fcntl, FOwnerEx, FcntlArg, OwnerType};
+    use nix::unistd::getpgrp;
+    use std::os::unix::io::AsRawFd;
+
+    let file = tempfile::tempfile().unwrap();
+    let fd = file.as_raw_fd();
+    let mut owner = FOwnerEx::new(OwnerType::F_OWNER_TID, 0);
+    fcntl(fd, FcntlArg::F_GETOWN_EX(&mut owner)).unwrap();
+    assert_eq!(owner.pid(), 0);
+
+    let pgrp = getpgrp().as_raw();
+    let owner = FOwnerEx::new(OwnerType::F_OWNER_PGRP, pgrp);
+    fcntl(fd, FcntlArg::F_SETOWN_EX(&owner)).unwrap();
+    let mut got = FOwnerEx::new(OwnerType::F_OWNER_TID, 0);
+    fcntl(fd, FcntlArg::F_GETOWN_EX(&mut got)).unwrap();
+    assert_eq!(got.owner_type(), Ok(OwnerType::F_OWNER_PGRP));
+    assert_eq!(got, owner);
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_f_notify() {
+    use nix::fcntl::{fcntl, DnotifyFlags, FcntlArg};
+    use std::os::unix::io::AsRawFd;
+
+    let dir = tempfile::tempdir().unwrap();
+    let dirfd = File::open(dir.path()).unwrap();
+    let fd = dirfd.as_raw_fd();
+    let flags = DnotifyFlags::DN_CREATE | DnotifyFlags::DN_MULTISHOT;
+    fcntl(fd, FcntlArg::F_NOTIFY(flags)).unwrap();
+    fcntl(fd, FcntlArg::F_NOTIFY(DnotifyFlags::empty())).unwrap();
+    // Only directories can be watched.
+    let file = tempfile::tempfile().unwrap();
+    assert_eq!(
+        fcntl(file.as_raw_fd(), FcntlArg::F_NOTIFY(flags)),
+        Err(Errno::ENOTDIR)
+    );
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_name_to_handle_at() {
+    use nix::fcntl::{
+        name_to_handle_at, open_by_handle_at, AtFlags, FileHandle,
+    };
+    use std::os::unix::io::AsFd;
+
+    use crate::*;
+
+    require_capability!("test_name_to_handle_at", CAP_DAC_READ_SEARCH);
+    let dir = tempfile::tempdir().unwrap();
+    let dirfd = File::open(dir.path()).unwrap();
+    std::fs::write(dir.path().join("a"), b"contents").unwrap();
+    let (handle, mount_id) =
+        match name_to_handle_at(Some(dirfd.as_fd()), "a", AtFlags::empty()) {
+            Err(Errno::EOPNOTSUPP) => {
+                skip!("the file system does not support file handles");
+            }
+            res => res.unwrap(),
+        };
+    assert!(mount_id.as_raw() > 0);
+    let bytes = handle.to_bytes();
+    assert_eq!(FileHandle::from_bytes(&bytes), Ok(handle.clone()));
+    assert_eq!(FileHandle::from_bytes(&bytes[..2]), Err(Errno::EINVAL));
+
+    // The handle keeps identifying the file after a rename.
+    std::fs::rename(dir.path().join("a"), dir.path().join("b")).unwrap();
+    let fd = open_by_handle_at(&dirfd, &handle, OFlag::O_RDONLY).unwrap();
+    let mut contents = String::new();
+    File::from(fd).read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "contents");
+    let (same, _) =
+        name_to_handle_at(Some(dirfd.as_fd()), "b", AtFlags::empty()).unwrap();
+    assert_eq!(same, handle);
+
+    std::fs::remove_file(dir.path().join("b")).unwrap();
+    assert_eq!(
+        open_by_handle_at(&dirfd, &handle, OFlag::O_RDONLY).unwrap_err(),
+        Errno::ESTALE
+    );
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_name_to_handle_at_fid() {
+    use nix::fcntl::{name_to_handle_at, AtFlags};
+
+    use crate::*;
+
+    let tmp = NamedTempFile::new().unwrap();
+    let flags = AtFlags::AT_HANDLE_FID;
+    let (handle, _) = match name_to_handle_at(None, tmp.path(), flags) {
+        Err(Errno::EINVAL) => {
+            skip!("AT_HANDLE_FID requires Linux 6.5");
+        }
+        Err(Errno::EOPNOTSUPP) => {
+            skip!("the file system does not support file handles");
+        }
+        res => res.unwrap(),
+    };
+    let (same, _) = name_to_handle_at(None, tmp.path(), flags).unwrap();
+    assert_eq!(same, handle);
+    let other = NamedTempFile::new().unwrap();
+    let (other, _) = name_to_handle_at(None, other.path(), flags).unwrap();
+    assert_ne!(other, handle);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_ficlone() {
+    use nix::fcntl::{ficlone, ficlonerange};
+
+    use crate::*;
+
+    let mut src = tempfile::tempfile().unwrap();
+    src.write_all(&[1; 8192]).unwrap();
+    let dest = tempfile::tempfile().unwrap();
+    match ficlone(&dest, &src) {
+        Err(Errno::EOPNOTSUPP) | Err(Errno::EINVAL) | Err(Errno::EXDEV) => {
+            skip!("the file system does not support reflinks");
+        }
+        res => res.unwrap(),
+    }
+    let mut contents = Vec::new();
+    (&dest).read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, [1; 8192]);
+
+    // Clone the second block over the first.
+    src.write_all(&[2; 4096]).unwrap();
+    let other = tempfile::tempfile().unwrap();
+    ficlonerange(&other, 0, &src, 8192, 4096).unwrap();
+    contents.clear();
+    (&other).read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, [2; 4096]);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_fideduperange() {
+    use nix::fcntl::{fideduperange, DedupeResult};
+    use std::os::unix::io::AsFd;
+
+    use crate::*;
+
+    let mut src = tempfile::tempfile().unwrap();
+    src.write_all(&[1; 4096]).unwrap();
+    let mut same = tempfile::tempfile().unwrap();
+    same.write_all(&[1; 4096]).unwrap();
+    let mut differs = tempfile::tempfile().unwrap();
+    differs.write_all(&[2; 4096]).unwrap();
+
+    let dests = [(same.as_fd(), 0), (differs.as_fd(), 0)];
+    let res = match fideduperange(&src, 0, 4096, &dests) {
+        Err(Errno::EOPNOTSUPP) | Err(Errno::EINVAL) => {
+            skip!("the file system does not support deduplication");
+        }
+        res => res.unwrap(),
+    };
+    assert_eq!(res, [DedupeResult::Deduped(4096), DedupeResult::Differs]);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_fiemap() {
+    use nix::fcntl::{fiemap, ExtentFlags, FiemapFlags};
+    use std::os::unix::fs::FileExt;
+
+    use crate::*;
+
+    // More extents than are queried at once, separated by holes.
+    const EXTENTS: u64 = 100;
+    let file = tempfile::tempfile().unwrap();
+    for i in 0..EXTENTS {
+        file.write_all_at(&[1; 4096], i * 16384).unwrap();
+    }
+
+    let extents = fiemap(&file, 0, u64::MAX, FiemapFlags::FIEMAP_FLAG_SYNC)
+        .collect::<nix::Result<Vec<_>>>();
+    let extents = match extents {
+        Err(Errno::EOPNOTSUPP) => {
+            skip!("the file system does not support FIEMAP");
+        }
+        res => res.unwrap(),
+    };
+    assert_eq!(extents.len() as u64, EXTENTS);
+    for (i, extent) in extents.iter().enumerate() {
+        assert_eq!(extent.logical, i as u64 * 16384);
+        assert_eq!(extent.length, 4096);
+    }
+    assert!(extents[..extents.len() - 1]
+        .iter()
+        .all(|e| !e.flags.contains(ExtentFlags::FIEMAP_EXTENT_LAST)));
+    assert!(extents
+        .last()
+        .unwrap()
+        .flags
+        .contains(ExtentFlags::FIEMAP_EXTENT_LAST));
+
+    // Only the extents overlapping the range are returned.
+    let extents = fiemap(&file, 20000, 20000, FiemapFlags::empty())
+        .collect::<nix::Result<Vec<_>>>()
+        .unwrap();
+    let logical: Vec<_> = extents.iter().map(|e| e.logical).collect();
+    assert_eq!(logical, [16384, 32768]);
+    assert_eq!(fiemap(&file, 0, 0, FiemapFlags::empty()).count(), 0);
+}
+
+#[test]
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "emscripten",
+    target_os = "fuchsia",
+    target_os = "wasi",
+    target_env = "uclibc",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "macos"
+))]
+fn test_prefetch() {
+    use nix::fcntl::{prefetch, Prefetch};
+    use nix::unistd::pipe;
+    use std::os::unix::io::{FromRawFd, OwnedFd};
+
+    let mut tmp = tempfile::tempfile().unwrap();
+    tmp.write_all(&[1; 8192]).unwrap();
+    let primitive = prefetch(&tmp, 0, 8192).unwrap();
+    #[cfg(target_os = "linux")]
+    assert_eq!(primitive, Prefetch::Readahead);
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    assert_eq!(primitive, Prefetch::RdAdvise);
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "ios",
+        target_os = "macos"
+    )))]
+    assert_eq!(primitive, Prefetch::Fadvise);
+
+    // Pipes cannot be read ahead with any of the primitives.
+    let (rd, wr) = pipe().unwrap();
+    let rd = unsafe { OwnedFd::from_raw_fd(rd) };
+    let _wr = unsafe { OwnedFd::from_raw_fd(wr) };
+    assert!(prefetch(&rd, 0, 8192).is_err());
+}
@@ -0,0 +1,120 @@
+use nix::proc::*;
+use nix::sys::mman::ProtFlags;
+use nix::sys::signal::{SigSet, SigmaskHow, Signal};
+use nix::unistd::{getpid, getppid, gettid, getuid, Pid};
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+
+#[test]
+fn test_memory_map_from_str() {
+    let map: MemoryMap =
+        "7f00a000-7f00c000 r-xs 00001000 fd:01 1234      /tmp/with space"
+            .parse()
+            .unwrap();
+    assert_eq!(map.range, 0x7f00a000..0x7f00c000);
+    assert_eq!(map.prot, ProtFlags::PROT_READ | ProtFlags::PROT_EXEC);
+    assert!(map.shared);
+    assert_eq!(map.offset, 0x1000);
+    assert_eq!(map.dev, libc::makedev(0xfd, 1));
+    assert_eq!(map.inode, 1234);
+    assert_eq!(map.path.as_deref(), Some(Path::new("/tmp/with space")));
+    assert_eq!(map.smaps, None);
+
+    let map: MemoryMap = "1000-2000 ---p 00000000 00:00 0".parse().unwrap();
+    assert_eq!(map.prot, ProtFlags::PROT_NONE);
+    assert!(!map.shared);
+    assert_eq!(map.path, None);
+
+    assert!("1000-2000 rw-p".parse::<MemoryMap>().is_err());
+}
+
+#[test]
+fn test_maps() {
+    let heap = Box::new([0u8; 16]);
+    let addr = heap.as_ptr() as usize;
+    let map = maps(Pid::this())
+        .unwrap()
+        .map(|map| map.unwrap())
+        .find(|map| map.range.contains(&addr))
+        .unwrap();
+    assert!(map
+        .prot
+        .contains(ProtFlags::PROT_READ | ProtFlags::PROT_WRITE));
+    assert!(!map.shared);
+
+    let code = test_maps as *const () as usize;
+    let map = maps(Pid::this())
+        .unwrap()
+        .map(|map| map.unwrap())
+        .find(|map| map.range.contains(&code))
+        .unwrap();
+    assert!(map.prot.contains(ProtFlags::PROT_EXEC));
+    assert_ne!(map.inode, 0);
+    assert_eq!(map.path.unwrap(), std::env::current_exe().unwrap());
+}
+
+#[test]
+fn test_smaps() {
+    let mut n = 0;
+    for map in smaps(Pid::this()).unwrap() {
+        let map = map.unwrap();
+        let smaps = map.smaps.unwrap();
+        assert_eq!(smaps.size, (map.range.end - map.range.start) as u64);
+        assert!(smaps.rss <= smaps.size);
+        n += 1;
+    }
+    assert_eq!(n, maps(Pid::this()).unwrap().count());
+}
+
+#[test]
+fn test_process_state_from_str() {
+    assert_eq!("R (running)".parse(), Ok(ProcessState::Running));
+    assert_eq!("x (dead)".parse(), Ok(ProcessState::Dead));
+    assert_eq!("W (waking)".parse(), Ok(ProcessState::Waking));
+    assert_eq!("K (wakekill)".parse(), Ok(ProcessState::WakeKill));
+    assert_eq!("N".parse(), Ok(ProcessState::Other('N')));
+    assert!("".parse::<ProcessState>().is_err());
+}
+
+#[test]
+fn test_status() {
+    let mut mask = SigSet::empty();
+    mask.add(Signal::SIGUSR2);
+    let old = mask.thread_swap_mask(SigmaskHow::SIG_BLOCK).unwrap();
+
+    let status = status(gettid());
+    old.thread_set_mask().unwrap();
+    let status = status.unwrap();
+    assert_eq!(status.state, ProcessState::Running);
+    assert_eq!(status.tgid, getpid());
+    assert_eq!(status.pid, gettid());
+    assert_eq!(status.ppid, getppid());
+    assert_eq!(status.uid.real, getuid());
+    assert!(status.threads >= 1);
+    assert!(status.sig_blocked.contains(Signal::SIGUSR2));
+    assert_eq!(status.cap_ambient & !status.cap_permitted, 0);
+    assert!(!status.name.is_empty());
+}
+
+#[test]
+fn test_tasks() {
+    let (tid_tx, tid_rx) = mpsc::channel();
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    let thread = thread::spawn(move || {
+        tid_tx.send(gettid()).unwrap();
+        done_rx.recv().unwrap();
+    });
+    let tid = tid_rx.recv().unwrap();
+
+    let tids = tasks(Pid::this())
+        .unwrap()
+        .collect::<nix::Result<Vec<_>>>()
+        .unwrap();
+    assert!(tids.contains(&getpid()));
+    assert!(tids.contains(&gettid()));
+    assert!(tids.contains(&tid));
+
+    done_tx.send(()).unwrap();
+    thread.join().unwrap();
+}
@@ -0,0 +1,44 @@
+use nix::ucontext::{Stack, UContext};
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+static MAIN: AtomicPtr<UContext> = AtomicPtr::new(std::ptr::null_mut());
+static COROUTINE: AtomicPtr<UContext> = AtomicPtr::new(std::ptr::null_mut());
+static STEPS: AtomicUsize = AtomicUsize::new(0);
+
+extern "C" fn coroutine() {
+    STEPS.fetch_add(1, Ordering::SeqCst);
+    let from = unsafe { &mut *COROUTINE.load(Ordering::SeqCst) };
+    let to = unsafe { &*MAIN.load(Ordering::SeqCst) };
+    unsafe { UContext::swap(from, to) }.unwrap();
+    STEPS.fetch_add(1, Ordering::SeqCst);
+    // Returning resumes the linked context.
+}
+
+#[test]
+fn test_swapcontext() {
+    let main = Box::into_raw(Box::new(UContext::get().unwrap()));
+    let co = Box::into_raw(Box::new(UContext::get().unwrap()));
+    let stack = Stack::new(64 * 1024).unwrap();
+    unsafe { (*co).make(&stack, Some(&*main), coroutine) }.unwrap();
+    MAIN.store(main, Ordering::SeqCst);
+    COROUTINE.store(co, Ordering::SeqCst);
+
+    unsafe { UContext::swap(&mut *main, &*co) }.unwrap();
+    assert_eq!(STEPS.load(Ordering::SeqCst), 1);
+    unsafe { UContext::swap(&mut *main, &*co) }.unwrap();
+    assert_eq!(STEPS.load(Ordering::SeqCst), 2);
+
+    unsafe {
+        drop(Box::from_raw(main));
+        drop(Box::from_raw(co));
+    }
+}
+
+#[test]
+fn test_stack_size() {
+    let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let stack = Stack::new(1).unwrap();
+    assert_eq!(stack.size(), page);
+    assert_eq!(stack.as_ptr() as usize % page, 0);
+    Stack::new(0).unwrap_err();
+}